@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 use perionav::core::edge::Edge;
 use perionav::core::node::Node;
 use perionav::core::Graph;
@@ -1,11 +1,17 @@
+pub mod avoidareas;
 pub mod components;
+pub mod conditional;
 pub mod edge;
 pub mod edgeinformation;
+pub mod geometry;
 pub mod graph;
+pub mod matching;
 pub mod node;
 pub mod path;
 pub mod routing;
+pub mod spatialindex;
 pub mod standardgraph;
+pub mod traffic;
 pub mod weight;
 
 pub use graph::*;
@@ -0,0 +1,108 @@
+use super::spatialindex::{segments_intersect, EdgeIndex};
+use super::weight::{AvoidAreasWeight, WeightCalculator};
+use super::Graph;
+
+use std::collections::HashSet;
+
+//areas that a route has to avoid, coordinates are (lat, lon)
+#[derive(Clone, Debug)]
+pub enum Area {
+    BBox {
+        min_lat: f64,
+        min_lon: f64,
+        max_lat: f64,
+        max_lon: f64,
+    },
+    Polygon(Vec<(f64, f64)>), //the polygon is closed automatically, the first point doesn't have to be repeated
+}
+
+impl Area {
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        match self {
+            Area::BBox {
+                min_lat,
+                min_lon,
+                max_lat,
+                max_lon,
+            } => *min_lat <= lat && lat <= *max_lat && *min_lon <= lon && lon <= *max_lon,
+            Area::Polygon(polygon) => point_in_polygon((lat, lon), polygon),
+        }
+    }
+}
+
+/// Wrap the weight calculator so that every edge that intersects one of the areas gets an infinite weight
+/// the index must be created from the same graph
+pub fn create_avoid_areas_weight_calculator(
+    weight_calculator: WeightCalculator,
+    graph: &impl Graph,
+    index: &EdgeIndex,
+    areas: &[Area],
+) -> WeightCalculator {
+    WeightCalculator::AvoidAreas(AvoidAreasWeight {
+        weight_calculator: Box::new(weight_calculator),
+        blocked_edges: find_blocked_edges(graph, index, areas),
+    })
+}
+
+//all edges intersecting one of the areas as (smallest node, largest node)
+pub fn find_blocked_edges(graph: &impl Graph, index: &EdgeIndex, areas: &[Area]) -> HashSet<(usize, usize)> {
+    let mut blocked_edges = HashSet::new();
+    for area in areas {
+        match area {
+            Area::BBox {
+                min_lat,
+                min_lon,
+                max_lat,
+                max_lon,
+            } => blocked_edges.extend(index.in_bbox(*min_lat, *min_lon, *max_lat, *max_lon)),
+            Area::Polygon(polygon) => {
+                if polygon.len() < 3 {
+                    continue;
+                }
+
+                //the bounding box gives the candidates, only those have to be checked against the polygon itself
+                let min_lat = polygon.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+                let max_lat = polygon.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+                let min_lon = polygon.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+                let max_lon = polygon.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+
+                for (node1, node2) in index.in_bbox(min_lat, min_lon, max_lat, max_lon) {
+                    if let (Some(from), Some(to)) = (graph.get_node(node1), graph.get_node(node2)) {
+                        if segment_intersects_polygon((from.lat, from.lon), (to.lat, to.lon), polygon) {
+                            blocked_edges.insert((node1, node2));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    blocked_edges
+}
+
+fn segment_intersects_polygon(from: (f64, f64), to: (f64, f64), polygon: &[(f64, f64)]) -> bool {
+    if point_in_polygon(from, polygon) || point_in_polygon(to, polygon) {
+        return true;
+    }
+
+    (0..polygon.len()).any(|i| segments_intersect(from, to, polygon[i], polygon[(i + 1) % polygon.len()]))
+}
+
+//ray casting, the polygon is treated as flat in degrees
+pub fn point_in_polygon(point: (f64, f64), polygon: &[(f64, f64)]) -> bool {
+    if polygon.is_empty() {
+        return false;
+    }
+
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.0 > point.0) != (b.0 > point.0) && point.1 < (b.1 - a.1) * (point.0 - a.0) / (b.0 - a.0) + a.1 {
+            inside = !inside;
+        }
+        j = i;
+    }
+
+    inside
+}
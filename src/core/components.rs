@@ -1,5 +1,9 @@
+pub mod biconnected;
+pub mod filter;
 pub mod kosaraju;
 pub mod options;
 pub mod pathbased;
 pub mod tarjan;
 pub mod tarjan2;
+pub mod vehicle;
+pub mod weakly;
@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+
+use crate::core::Graph;
+
+//the direction of the edges is ignored: a road that can only be used one way still connects both sides when it is closed
+
+/// An edge whose closure splits its connected component in two
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bridge {
+    pub nodes: (usize, usize), //(smallest node, largest node)
+    pub nodes_cut_off: usize,  //the number of nodes on the smallest side
+}
+
+#[derive(Debug, Default)]
+pub struct BiconnectedComponents {
+    pub articulation_points: HashSet<usize>, //nodes whose removal splits their connected component
+    pub bridges: Vec<Bridge>,
+    pub components: Vec<HashSet<usize>>, //maximal sets of nodes that stay connected when any single node is removed, articulation points are in more than one
+}
+
+struct AlgorithmData {
+    adj_nodes: Vec<Vec<usize>>,
+    index: usize,
+    indices: Vec<Option<usize>>, //the order in which the nodes are explored
+    low_links: Vec<usize>,
+    sizes: Vec<usize>,               //the number of nodes in the subtree of the search
+    edge_stack: Vec<(usize, usize)>, //the edges of the components that aren't finished yet
+    result: BiconnectedComponents,
+}
+
+impl AlgorithmData {
+    fn new(graph: &impl Graph) -> Self {
+        let nr_nodes = graph.get_nr_nodes();
+        let mut adj_nodes = vec![vec![]; nr_nodes];
+        for (node, adj) in adj_nodes.iter_mut().enumerate() {
+            graph.do_for_all_neighbors(node, false, |adj_node| adj.push(adj_node));
+            graph.do_for_all_neighbors(node, true, |adj_node| adj.push(adj_node));
+            adj.retain(|adj_node| *adj_node != node);
+            adj.sort_unstable();
+            adj.dedup();
+        }
+
+        AlgorithmData {
+            adj_nodes,
+            index: 0,
+            indices: vec![None; nr_nodes],
+            low_links: vec![0; nr_nodes],
+            sizes: vec![1; nr_nodes],
+            edge_stack: vec![],
+            result: BiconnectedComponents::default(),
+        }
+    }
+
+    fn visit(&mut self, node: usize) {
+        self.indices[node] = Some(self.index);
+        self.low_links[node] = self.index;
+        self.index += 1;
+    }
+
+    //iterative depth first search from the root, the stack has (node, parent, position of the next adj node)
+    fn search(&mut self, root: usize) {
+        let first_bridge = self.result.bridges.len();
+        let mut root_children = 0;
+
+        self.visit(root);
+        let mut stack = vec![(root, usize::MAX, 0)];
+        while let Some((node, parent, next)) = stack.last_mut() {
+            let (node, parent) = (*node, *parent);
+            if let Some(adj_node) = self.adj_nodes[node].get(*next).copied() {
+                *next += 1;
+                if adj_node == parent {
+                    continue;
+                }
+
+                match self.indices[adj_node] {
+                    None => {
+                        self.visit(adj_node);
+                        self.edge_stack.push((node, adj_node));
+                        stack.push((adj_node, node, 0));
+                        if node == root {
+                            root_children += 1;
+                        }
+                    }
+                    //an edge back to a node higher up in the search
+                    Some(adj_index) if adj_index < self.indices[node].unwrap() => {
+                        self.low_links[node] = self.low_links[node].min(adj_index);
+                        self.edge_stack.push((node, adj_node));
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            stack.pop();
+            if parent == usize::MAX {
+                continue;
+            }
+
+            self.low_links[parent] = self.low_links[parent].min(self.low_links[node]);
+            self.sizes[parent] += self.sizes[node];
+            let parent_index = self.indices[parent].unwrap();
+
+            if self.low_links[node] > parent_index {
+                self.result.bridges.push(Bridge {
+                    nodes: (parent.min(node), parent.max(node)),
+                    nodes_cut_off: self.sizes[node], //corrected once the size of the whole component is known
+                });
+            }
+
+            //nothing below the node reaches above the parent, so the edges since (parent, node) are a component
+            if self.low_links[node] >= parent_index {
+                if parent != root {
+                    self.result.articulation_points.insert(parent);
+                }
+
+                let mut component = HashSet::new();
+                while let Some((from, to)) = self.edge_stack.pop() {
+                    component.insert(from);
+                    component.insert(to);
+                    if (from, to) == (parent, node) {
+                        break;
+                    }
+                }
+                self.result.components.push(component);
+            }
+        }
+
+        if root_children > 1 {
+            self.result.articulation_points.insert(root);
+        }
+
+        let component_size = self.sizes[root];
+        for bridge in self.result.bridges[first_bridge..].iter_mut() {
+            bridge.nodes_cut_off = bridge.nodes_cut_off.min(component_size - bridge.nodes_cut_off);
+        }
+    }
+}
+
+/// Articulation points, bridges and biconnected components of the graph without the direction of the edges (Hopcroft-Tarjan)
+/// the bridges are sorted with the largest number of nodes cut off first
+pub fn get_biconnected_components(graph: &impl Graph) -> BiconnectedComponents {
+    let mut algorithm_data = AlgorithmData::new(graph);
+    for node in 0..graph.get_nr_nodes() {
+        if algorithm_data.indices[node].is_none() {
+            algorithm_data.search(node);
+        }
+    }
+
+    let mut result = algorithm_data.result;
+    result
+        .bridges
+        .sort_by_key(|bridge| (std::cmp::Reverse(bridge.nodes_cut_off), bridge.nodes));
+    result
+}
@@ -0,0 +1,53 @@
+use std::collections::HashSet;
+
+use super::options::Neighbors;
+use super::weakly::get_weakly_connected_components;
+
+/// Which strongly connected components are kept
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ComponentsPolicy {
+    /// only the largest component, islands, areas that are only reachable by ferry and oneway dead ends are dropped
+    #[default]
+    Largest,
+    /// every component with at least this many nodes
+    MinSize(usize),
+    /// the largest component of every weakly connected part of the graph, so islands without a ferry in the graph keep their roads
+    LargestPerIsland,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ComponentsFilter {
+    pub policy: ComponentsPolicy,
+    pub mark_only: bool, //keep every node, but only snap to the nodes of the kept components
+}
+
+/// The nodes of the components that the policy keeps, components are the result of Graph::get_strongly_connected_subgraphs
+/// or of get_vehicle_components, with a VehicleNeighbors graph for the islands of that vehicle type
+pub fn get_kept_nodes(graph: &impl Neighbors, components: Vec<HashSet<usize>>, policy: ComponentsPolicy) -> HashSet<usize> {
+    match policy {
+        ComponentsPolicy::Largest => components.into_iter().max_by_key(|c| c.len()).unwrap_or_default(),
+        ComponentsPolicy::MinSize(min_size) => components.into_iter().filter(|c| c.len() >= min_size).flatten().collect(),
+        ComponentsPolicy::LargestPerIsland => {
+            let mut islands = vec![0; graph.get_nr_nodes()];
+            for (island, nodes) in get_weakly_connected_components(graph).into_iter().enumerate() {
+                for node in nodes {
+                    islands[node] = island;
+                }
+            }
+
+            //a strongly connected component is always inside a single island
+            let mut largest: Vec<Option<HashSet<usize>>> = vec![None; islands.len()];
+            for component in components {
+                let Some(node) = component.iter().next() else {
+                    continue;
+                };
+                let current = &mut largest[islands[*node]];
+                if current.as_ref().is_none_or(|c| c.len() < component.len()) {
+                    *current = Some(component);
+                }
+            }
+
+            largest.into_iter().flatten().flatten().collect()
+        }
+    }
+}
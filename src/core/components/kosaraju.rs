@@ -1,8 +1,6 @@
 use std::collections::HashSet;
 
-use crate::core::Graph;
-
-use super::options::ComponentsAlgorithm;
+use super::options::{ComponentsAlgorithm, Neighbors};
 
 pub struct KosarajuComponentsAlgorithm {}
 
@@ -26,7 +24,7 @@ impl AlgorithmDataPart2 {
         }
     }
 
-    fn create_component(&mut self, graph: &impl Graph, start_node: usize) {
+    fn create_component(&mut self, graph: &impl Neighbors, start_node: usize) {
         let mut component = HashSet::new();
 
         let mut stack = Vec::new();
@@ -54,7 +52,7 @@ impl AlgorithmData {
         }
     }
 
-    fn visit(&mut self, graph: &impl Graph, start_node: usize) {
+    fn visit(&mut self, graph: &impl Neighbors, start_node: usize) {
         let mut stack = Vec::new();
         stack.push((start_node, true)); //boolean is whether or not we should visit the neighbors
 
@@ -76,7 +74,7 @@ impl AlgorithmData {
     }
 }
 
-impl<G: Graph> ComponentsAlgorithm<G> for KosarajuComponentsAlgorithm {
+impl<G: Neighbors> ComponentsAlgorithm<G> for KosarajuComponentsAlgorithm {
     fn get_components(&self, graph: &G) -> Vec<HashSet<usize>> {
         let mut algorithm_data = AlgorithmData::new();
 
@@ -15,23 +15,44 @@ pub enum AlgorithmType {
     KOSARAJU,
 }
 
+/// The part of a graph that the components algorithms use, so that they can also run on the edges of a single vehicle type
+pub trait Neighbors {
+    fn get_nr_nodes(&self) -> usize;
+    fn do_for_all_neighbors<F>(&self, base_node: usize, reverse: bool, f: F)
+    where
+        F: FnMut(usize);
+}
+
+impl<G: Graph> Neighbors for G {
+    fn get_nr_nodes(&self) -> usize {
+        Graph::get_nr_nodes(self)
+    }
+
+    fn do_for_all_neighbors<F>(&self, base_node: usize, reverse: bool, f: F)
+    where
+        F: FnMut(usize),
+    {
+        Graph::do_for_all_neighbors(self, base_node, reverse, f)
+    }
+}
+
 /// Calculate strongly connected subcomponents of a graph
-pub trait ComponentsAlgorithm<G: Graph> {
+pub trait ComponentsAlgorithm<G: Neighbors> {
     fn get_components(&self, graph: &G) -> Vec<HashSet<usize>>;
 }
 
-pub struct ComponentsAlgorithmOptions<G: Graph> {
+pub struct ComponentsAlgorithmOptions<G: Neighbors> {
     pub components_algorithm: Box<dyn ComponentsAlgorithm<G>>,
 }
 
-impl<G: Graph> ComponentsAlgorithmOptions<G> {
+impl<G: Neighbors> ComponentsAlgorithmOptions<G> {
     pub fn new(algorithm_type: AlgorithmType) -> Self {
         let components_algorithm = create_components_algorithm(&algorithm_type);
         ComponentsAlgorithmOptions { components_algorithm }
     }
 }
 
-pub fn create_components_algorithm<G: Graph>(algorithm_type: &AlgorithmType) -> Box<dyn ComponentsAlgorithm<G>> {
+pub fn create_components_algorithm<G: Neighbors>(algorithm_type: &AlgorithmType) -> Box<dyn ComponentsAlgorithm<G>> {
     match algorithm_type {
         AlgorithmType::PATHBASED => Box::new(PathBasedComponentsAlgorithm::new()),
         AlgorithmType::TARJAN => Box::new(TarjanComponentsAlgorithm {}),
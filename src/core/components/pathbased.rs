@@ -1,8 +1,6 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::core::Graph;
-
-use super::options::ComponentsAlgorithm;
+use super::options::{ComponentsAlgorithm, Neighbors};
 
 //implementation of https://en.wikipedia.org/wiki/Path-based_strong_component_algorithm
 
@@ -42,7 +40,7 @@ impl PathBasedComponentsAlgorithm {
         PathBasedComponentsAlgorithm {}
     }
 
-    fn determine_components_from_node(&self, algorithm_data: &mut AlgorithmData, graph: &impl Graph, start_index: usize) {
+    fn determine_components_from_node(&self, algorithm_data: &mut AlgorithmData, graph: &impl Neighbors, start_index: usize) {
         let mut stack = Vec::new();
         stack.push((start_index, true)); //boolean is whether or not we should visit the neighbors
                                          //after visiting a node, the same node will be pushed with false, so that it can be handled after all of the subtree of the current node is handled
@@ -88,7 +86,7 @@ impl PathBasedComponentsAlgorithm {
     }
 }
 
-impl<G: Graph> ComponentsAlgorithm<G> for PathBasedComponentsAlgorithm {
+impl<G: Neighbors> ComponentsAlgorithm<G> for PathBasedComponentsAlgorithm {
     fn get_components(&self, graph: &G) -> Vec<HashSet<usize>> {
         let mut algorithm_data = AlgorithmData::new();
 
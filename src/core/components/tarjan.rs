@@ -1,8 +1,6 @@
 use std::{collections::HashSet, fmt};
 
-use crate::core::Graph;
-
-use super::options::ComponentsAlgorithm;
+use super::options::{ComponentsAlgorithm, Neighbors};
 
 /*
 this iterative implementation is created by myself
@@ -56,7 +54,7 @@ impl AlgorithmData {
     }
 
     #[allow(dead_code)]
-    fn strongconnect_recursive(&mut self, graph: &impl Graph, node_index: usize) {
+    fn strongconnect_recursive(&mut self, graph: &impl Neighbors, node_index: usize) {
         match self.nodes[node_index] {
             None => {
                 self.nodes[node_index] = Some(Box::new(AlgorithmNode {
@@ -114,7 +112,7 @@ impl AlgorithmData {
         }
     }
 
-    fn strongconnect_iterative(&mut self, graph: &impl Graph, node_index: usize) {
+    fn strongconnect_iterative(&mut self, graph: &impl Neighbors, node_index: usize) {
         let mut stack: Vec<(usize, State)> = Vec::new();
         stack.push((node_index, State::Initial));
 
@@ -198,7 +196,7 @@ impl AlgorithmData {
     }
 }
 
-impl<G: Graph> ComponentsAlgorithm<G> for TarjanComponentsAlgorithm {
+impl<G: Neighbors> ComponentsAlgorithm<G> for TarjanComponentsAlgorithm {
     fn get_components(&self, graph: &G) -> Vec<HashSet<usize>> {
         let mut algorithm_data = AlgorithmData::new(graph.get_nr_nodes());
 
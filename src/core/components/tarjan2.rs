@@ -1,8 +1,6 @@
 use std::{collections::HashSet, fmt};
 
-use crate::core::Graph;
-
-use super::options::ComponentsAlgorithm;
+use super::options::{ComponentsAlgorithm, Neighbors};
 
 /*
 this iterative implementation has been translated from some java implementation
@@ -55,7 +53,7 @@ impl AlgorithmData {
         }
     }
 
-    fn strongconnect_iterative(&mut self, graph: &impl Graph, node_index: usize) {
+    fn strongconnect_iterative(&mut self, graph: &impl Neighbors, node_index: usize) {
         let mut stack: Vec<(usize, State)> = Vec::new();
         stack.push((node_index, State::FindComponent));
 
@@ -120,7 +118,7 @@ impl AlgorithmData {
     }
 
     fn add_node(&mut self, node_index: usize) {
-        if let None = self.nodes[node_index] {
+        if self.nodes[node_index].is_none() {
             self.nodes[node_index] = Some(Box::new(AlgorithmNode {
                 index: self.index,
                 low_link: self.index,
@@ -133,7 +131,7 @@ impl AlgorithmData {
     }
 }
 
-impl<G: Graph> ComponentsAlgorithm<G> for TarjanComponentsAlgorithm2 {
+impl<G: Neighbors> ComponentsAlgorithm<G> for TarjanComponentsAlgorithm2 {
     fn get_components(&self, graph: &G) -> Vec<HashSet<usize>> {
         let mut algorithm_data = AlgorithmData::new(graph.get_nr_nodes());
 
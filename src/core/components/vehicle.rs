@@ -0,0 +1,46 @@
+use std::collections::HashSet;
+
+use crate::core::edge::VehicleTypes;
+use crate::core::Graph;
+
+use super::options::{create_components_algorithm, AlgorithmType, Neighbors};
+
+/// The edges of the graph that a vehicle type can use, also the ones it can only use at certain times
+/// so that a node can be in the main network for bikes but not for cars
+pub struct VehicleNeighbors<'g, G: Graph> {
+    graph: &'g G,
+    vehicle_type: VehicleTypes,
+}
+
+impl<'g, G: Graph> VehicleNeighbors<'g, G> {
+    pub fn new(graph: &'g G, vehicle_type: VehicleTypes) -> Self {
+        VehicleNeighbors { graph, vehicle_type }
+    }
+}
+
+impl<G: Graph> Neighbors for VehicleNeighbors<'_, G> {
+    fn get_nr_nodes(&self) -> usize {
+        self.graph.get_nr_nodes()
+    }
+
+    fn do_for_all_neighbors<F>(&self, base_node: usize, reverse: bool, mut f: F)
+    where
+        F: FnMut(usize),
+    {
+        self.graph.do_for_all_neighbors(base_node, reverse, |adj_node| {
+            if self
+                .graph
+                .get_directed_vehicle_specific_edge_information(base_node, adj_node, reverse, self.vehicle_type)
+                .is_some()
+            {
+                f(adj_node);
+            }
+        });
+    }
+}
+
+/// The strongly connected components of the edges that the vehicle type can use
+pub fn get_vehicle_components<G: Graph>(graph: &G, vehicle_type: VehicleTypes, algorithm_type: &AlgorithmType) -> Vec<HashSet<usize>> {
+    let neighbors = VehicleNeighbors::new(graph, vehicle_type);
+    create_components_algorithm(algorithm_type).get_components(&neighbors)
+}
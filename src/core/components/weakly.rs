@@ -0,0 +1,72 @@
+use std::collections::{HashMap, HashSet};
+
+use super::options::Neighbors;
+
+//union find with path halving and union by size
+struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(nr_nodes: usize) -> Self {
+        UnionFind {
+            parents: (0..nr_nodes).collect(),
+            sizes: vec![1; nr_nodes],
+        }
+    }
+
+    fn find(&mut self, mut node: usize) -> usize {
+        while self.parents[node] != node {
+            self.parents[node] = self.parents[self.parents[node]];
+            node = self.parents[node];
+        }
+        node
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+
+        let (small, large) = if self.sizes[a] < self.sizes[b] { (a, b) } else { (b, a) };
+        self.parents[small] = large;
+        self.sizes[large] += self.sizes[small];
+    }
+}
+
+/// The nodes that are connected when the direction of the edges is ignored, every node is in exactly one component
+pub fn get_weakly_connected_components(graph: &impl Neighbors) -> Vec<HashSet<usize>> {
+    let nr_nodes = graph.get_nr_nodes();
+    let mut union_find = UnionFind::new(nr_nodes);
+    for node in 0..nr_nodes {
+        //an edge that can only be used backwards is only a neighbor of the adj node, which is enough to join them
+        graph.do_for_all_neighbors(node, false, |adj_node| union_find.union(node, adj_node));
+    }
+
+    let mut components: HashMap<usize, HashSet<usize>> = HashMap::new();
+    for node in 0..nr_nodes {
+        components.entry(union_find.find(node)).or_default().insert(node);
+    }
+
+    components.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut union_find = UnionFind::new(5);
+        union_find.union(0, 1);
+        union_find.union(3, 4);
+        union_find.union(1, 4);
+
+        assert_eq!(union_find.find(0), union_find.find(3));
+        assert_ne!(union_find.find(0), union_find.find(2));
+        let root = union_find.find(4);
+        assert_eq!(union_find.sizes[root], 4);
+    }
+}
@@ -0,0 +1,214 @@
+//time based restrictions like access:conditional=no @ (Mo-Fr 07:00-19:00)
+//times are local times: seconds since the unix epoch plus the utc offset of the area
+
+const DAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+//the subset of the opening_hours syntax that conditions use: weekdays, times and off
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpeningHours {
+    rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Rule {
+    days: [bool; 7],        //monday first
+    times: Vec<(u32, u32)>, //minutes since midnight, the end can be before the start for times past midnight
+    off: bool,              //the rule closes instead of opens
+}
+
+impl OpeningHours {
+    /// Parses rules like "Mo-Fr 07:00-09:00,15:00-16:00; Sa 10:00-12:00", "Sa,Su", "22:00-06:00" or "24/7"
+    /// returns None for anything else, like public holidays or dates, so that a condition that isn't understood isn't applied
+    pub fn parse(value: &str) -> Option<Self> {
+        let rules = value
+            .split(';')
+            .filter(|r| !r.trim().is_empty())
+            .map(parse_rule)
+            .collect::<Option<Vec<Rule>>>()?;
+        if rules.is_empty() {
+            return None;
+        }
+
+        Some(OpeningHours { rules })
+    }
+
+    pub fn is_open(&self, local_time: f64) -> bool {
+        let days = (local_time / 86400.0).floor();
+        let day = (days as i64 + 3).rem_euclid(7) as usize; //the unix epoch is on a thursday
+        let minute = ((local_time - days * 86400.0) / 60.0) as u32;
+
+        let matches = |rule: &Rule| {
+            rule.times.iter().any(|&(start, end)| {
+                if start < end {
+                    rule.days[day] && start <= minute && minute < end
+                } else {
+                    //past midnight, the end belongs to the next day
+                    (rule.days[day] && minute >= start) || (rule.days[(day + 6) % 7] && minute < end)
+                }
+            })
+        };
+
+        self.rules.iter().any(|r| !r.off && matches(r)) && !self.rules.iter().any(|r| r.off && matches(r))
+    }
+}
+
+fn parse_rule(rule: &str) -> Option<Rule> {
+    let mut days = None;
+    let mut times = None;
+    let mut off = false;
+
+    for token in rule.split_whitespace() {
+        if token == "24/7" {
+            times = Some(vec![(0, MINUTES_PER_DAY)]);
+        } else if token == "off" || token == "closed" {
+            off = true;
+        } else if token.contains(':') {
+            times = Some(token.split(',').map(parse_time_range).collect::<Option<Vec<_>>>()?);
+        } else {
+            days = Some(parse_days(token)?);
+        }
+    }
+
+    Some(Rule {
+        days: days.unwrap_or([true; 7]),
+        times: times.unwrap_or(vec![(0, MINUTES_PER_DAY)]),
+        off,
+    })
+}
+
+//Mo-Fr,Su
+fn parse_days(token: &str) -> Option<[bool; 7]> {
+    let day_index = |day: &str| DAYS.iter().position(|d| *d == day);
+
+    let mut days = [false; 7];
+    for part in token.split(',') {
+        match part.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (day_index(from)?, day_index(to)?);
+                //ranges like Fr-Mo wrap around the end of the week
+                let mut day = from;
+                loop {
+                    days[day] = true;
+                    if day == to {
+                        break;
+                    }
+                    day = (day + 1) % 7;
+                }
+            }
+            None => days[day_index(part)?] = true,
+        }
+    }
+
+    Some(days)
+}
+
+//07:00-19:00
+fn parse_time_range(range: &str) -> Option<(u32, u32)> {
+    let (start, end) = range.split_once('-')?;
+    let (start, end) = (parse_time(start)?, parse_time(end)?);
+    if start == end {
+        return None;
+    }
+
+    Some((start, end))
+}
+
+fn parse_time(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.split_once(':')?;
+    let (hours, minutes) = (hours.parse::<u32>().ok()?, minutes.parse::<u32>().ok()?);
+    if hours > 24 || minutes >= 60 || hours * 60 + minutes > MINUTES_PER_DAY {
+        return None;
+    }
+
+    Some(hours * 60 + minutes)
+}
+
+/// Access of one vehicle type while the condition holds, None keeps the access of that direction
+/// later restrictions of the same edge have priority over earlier ones
+#[derive(Clone, Debug)]
+pub struct ConditionalRestriction {
+    pub forward: Option<bool>,
+    pub backward: Option<bool>,
+    pub hours: OpeningHours,
+}
+
+impl ConditionalRestriction {
+    pub fn grants_access(&self) -> bool {
+        self.forward == Some(true) || self.backward == Some(true)
+    }
+}
+
+//splits a value like "no @ (Mo-Fr 07:00-19:00); delivery @ (05:00-11:00)" in its values and conditions
+//conditions that aren't times (like weight > 7.5) are left out
+pub fn parse_conditional_value(value: &str) -> Vec<(&str, OpeningHours)> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ';' if depth == 0 => {
+                parts.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+
+    parts
+        .into_iter()
+        .filter_map(|part| {
+            let (value, condition) = part.split_once('@')?;
+            let condition = condition.trim();
+            let condition = condition.strip_prefix('(').and_then(|c| c.strip_suffix(')')).unwrap_or(condition);
+            Some((value.trim(), OpeningHours::parse(condition)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONDAY: f64 = 1709510400.0; //2024-03-04T00:00:00Z
+    const HOUR: f64 = 3600.0;
+
+    #[test]
+    fn test_opening_hours() {
+        let hours = OpeningHours::parse("Mo-Fr 07:45-08:30,15:00-15:45").unwrap();
+        assert!(hours.is_open(MONDAY + 8.0 * HOUR));
+        assert!(!hours.is_open(MONDAY + 9.0 * HOUR));
+        assert!(hours.is_open(MONDAY + 4.0 * 24.0 * HOUR + 15.5 * HOUR));
+        assert!(!hours.is_open(MONDAY + 5.0 * 24.0 * HOUR + 8.0 * HOUR));
+
+        //past midnight on sunday ends on monday
+        let hours = OpeningHours::parse("Sa,Su 22:00-06:00").unwrap();
+        assert!(hours.is_open(MONDAY + 5.0 * HOUR));
+        assert!(!hours.is_open(MONDAY + 23.0 * HOUR));
+        assert!(hours.is_open(MONDAY - 1.0 * HOUR));
+
+        let hours = OpeningHours::parse("24/7; Su off").unwrap();
+        assert!(hours.is_open(MONDAY));
+        assert!(!hours.is_open(MONDAY - 1.0 * HOUR));
+
+        assert!(OpeningHours::parse("Fr-Mo").unwrap().is_open(MONDAY));
+        assert!(OpeningHours::parse("PH 10:00-12:00").is_none());
+        assert!(OpeningHours::parse("Mo 25:00-26:00").is_none());
+        assert!(OpeningHours::parse("").is_none());
+    }
+
+    #[test]
+    fn test_conditional_value() {
+        let values = parse_conditional_value("no @ (Mo-Fr 07:00-09:00; Sa 10:00-12:00); delivery @ (05:00-11:00); no @ (weight > 7.5)");
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].0, "no");
+        assert_eq!(values[1].0, "delivery");
+        assert!(values[0].1.is_open(MONDAY + 5.0 * 24.0 * HOUR + 11.0 * HOUR));
+
+        assert_eq!(parse_conditional_value("yes @ Mo-Fr").len(), 1);
+        assert!(parse_conditional_value("yes").is_empty());
+    }
+}
@@ -1,15 +1,20 @@
+use serde::Deserialize;
 use std::{collections::HashMap, rc::Rc};
 
+use super::conditional::{ConditionalRestriction, OpeningHours};
+
 pub struct Edge {
     forward: bool,
 
     edge_info: Rc<HashMap<VehicleTypes, VehicleSpecificEdgeInformation>>,
 }
 
-#[derive(Eq, PartialEq, Hash)]
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum VehicleTypes {
     Car,
     Bike,
+    Hgv,
 }
 
 pub struct VehicleSpecificEdgeInformation {
@@ -20,12 +25,77 @@ pub struct VehicleSpecificEdgeInformation {
 //properties that stay the same in either direction
 pub struct UndirectedVehicleSpecificEdgeInformation {
     distance: f64,
+    attributes: EdgeAttributes,
+}
+
+//properties of the road itself, custom weights can use them at query time
+#[derive(Clone, Debug, Default)]
+pub struct EdgeAttributes {
+    pub road_class: RoadClass,
+    pub toll: bool,
+    pub ferry: bool,
+    pub way_id: Option<i64>, //the osm way the edge was created from
+    pub restrictions: PhysicalRestrictions,
+    pub time_penalty: f64, //seconds added to the travel time, for example for the barrier of a toll booth
+}
+
+//legal limits of the road, None if there is no limit. Weights are in tonnes, sizes in metres
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PhysicalRestrictions {
+    pub max_weight: Option<f64>,
+    pub max_axle_load: Option<f64>,
+    pub max_height: Option<f64>,
+    pub max_width: Option<f64>,
+    pub max_length: Option<f64>,
+    pub no_hazmat: bool, //vehicles with hazardous goods aren't allowed
+}
+
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoadClass {
+    Motorway,
+    Trunk,
+    Primary,
+    Secondary,
+    Tertiary,
+    Residential,
+    Service,
+    #[default]
+    Other,
+}
+
+impl RoadClass {
+    //links belong to the class of the road they are a link of
+    pub fn from_highway(highway: &str) -> Self {
+        match highway.strip_suffix("_link").unwrap_or(highway) {
+            "motorway" | "motorroad" => RoadClass::Motorway,
+            "trunk" => RoadClass::Trunk,
+            "primary" => RoadClass::Primary,
+            "secondary" => RoadClass::Secondary,
+            "tertiary" => RoadClass::Tertiary,
+            "residential" | "living_street" | "unclassified" => RoadClass::Residential,
+            "service" => RoadClass::Service,
+            _ => RoadClass::Other,
+        }
+    }
 }
 
 pub struct DirectedVehicleSpecificEdgeInformation {
     undirected_data: Rc<UndirectedVehicleSpecificEdgeInformation>,
-    speed: f64,
-    _acecssible: bool,
+    speed: f64, //in m/s
+    accessible: bool,
+    conditions: Vec<(bool, OpeningHours)>, //the access while the opening hours hold
+    time_penalty: f64,                     //seconds added in this direction only, for example for a barrier at the node the edge enters
+}
+
+//access and speeds of one vehicle type, in the direction of the edge and in the opposite direction
+#[derive(Clone, Debug)]
+pub struct VehicleAccess {
+    pub forward: bool,
+    pub backward: bool,
+    pub forward_speed: f64, //in m/s
+    pub backward_speed: f64,
+    pub conditions: Vec<ConditionalRestriction>,
 }
 
 impl Edge {
@@ -33,25 +103,65 @@ impl Edge {
 
     #[inline]
     pub fn new(distance: f64, is_forward: bool, is_backward: bool) -> Self {
-        let undirected_data = Rc::new(UndirectedVehicleSpecificEdgeInformation { distance });
+        let access = VehicleAccess {
+            forward: is_forward,
+            backward: is_backward,
+            forward_speed: 1.0,
+            backward_speed: 1.0,
+            conditions: vec![],
+        };
+
+        Self::with_vehicle_access(distance, vec![(VehicleTypes::Car, access)])
+    }
+
+    pub fn with_vehicle_access(distance: f64, vehicle_access: Vec<(VehicleTypes, VehicleAccess)>) -> Self {
+        Self::with_attributes(distance, EdgeAttributes::default(), vehicle_access)
+    }
+
+    pub fn with_attributes(distance: f64, attributes: EdgeAttributes, vehicle_access: Vec<(VehicleTypes, VehicleAccess)>) -> Self {
+        Self::with_time_penalties(distance, attributes, vehicle_access, (0.0, 0.0))
+    }
+
+    //(forward, backward) penalties on top of the time penalty of the attributes, which applies to both directions
+    pub fn with_time_penalties(
+        distance: f64,
+        attributes: EdgeAttributes,
+        vehicle_access: Vec<(VehicleTypes, VehicleAccess)>,
+        (forward_penalty, backward_penalty): (f64, f64),
+    ) -> Self {
+        let undirected_data = Rc::new(UndirectedVehicleSpecificEdgeInformation { distance, attributes });
         let mut edge_info = HashMap::new();
-        edge_info.insert(
-            VehicleTypes::Car,
-            VehicleSpecificEdgeInformation {
-                directed_info: (
-                    Rc::new(DirectedVehicleSpecificEdgeInformation {
-                        undirected_data: Rc::clone(&undirected_data),
-                        speed: 1.0,
-                        _acecssible: is_forward,
-                    }),
-                    Rc::new(DirectedVehicleSpecificEdgeInformation {
-                        undirected_data: Rc::clone(&undirected_data),
-                        speed: 1.0,
-                        _acecssible: is_backward,
-                    }),
-                ),
-            },
-        );
+        for (vehicle_type, access) in vehicle_access {
+            let conditions = |direction: fn(&ConditionalRestriction) -> Option<bool>| {
+                access
+                    .conditions
+                    .iter()
+                    .filter_map(|c| direction(c).map(|a| (a, c.hours.clone())))
+                    .collect()
+            };
+
+            edge_info.insert(
+                vehicle_type,
+                VehicleSpecificEdgeInformation {
+                    directed_info: (
+                        Rc::new(DirectedVehicleSpecificEdgeInformation {
+                            undirected_data: Rc::clone(&undirected_data),
+                            speed: access.forward_speed,
+                            accessible: access.forward,
+                            conditions: conditions(|c| c.forward),
+                            time_penalty: forward_penalty,
+                        }),
+                        Rc::new(DirectedVehicleSpecificEdgeInformation {
+                            undirected_data: Rc::clone(&undirected_data),
+                            speed: access.backward_speed,
+                            accessible: access.backward,
+                            conditions: conditions(|c| c.backward),
+                            time_penalty: backward_penalty,
+                        }),
+                    ),
+                },
+            );
+        }
 
         Edge {
             forward: true,
@@ -66,12 +176,13 @@ impl Edge {
         }
     }
 
+    //also true if the edge is only accessible at certain times
     pub fn is_forward(&self, vehicle_type: VehicleTypes) -> bool {
         self.edge_info.get(&vehicle_type).is_some_and(|e| {
             if self.forward {
-                e.directed_info.0._acecssible
+                e.directed_info.0.is_accessible_sometimes()
             } else {
-                e.directed_info.1._acecssible
+                e.directed_info.1.is_accessible_sometimes()
             }
         })
     }
@@ -79,25 +190,38 @@ impl Edge {
     pub fn is_backward(&self, vehicle_type: VehicleTypes) -> bool {
         self.edge_info.get(&vehicle_type).is_some_and(|e| {
             if self.forward {
-                e.directed_info.1._acecssible
+                e.directed_info.1.is_accessible_sometimes()
             } else {
-                e.directed_info.0._acecssible
+                e.directed_info.0.is_accessible_sometimes()
             }
         })
     }
 
+    //if at least one vehicle type can use the edge (forward direction and backward direction)
+    pub fn is_accessible_by_any_vehicle(&self) -> (bool, bool) {
+        self.edge_info
+            .keys()
+            .fold((false, false), |(fwd, bwd), vehicle_type| (fwd || self.is_forward(*vehicle_type), bwd || self.is_backward(*vehicle_type)))
+    }
+
     pub fn get_directed_vehicle_specific_edge_information(
         &self,
         vehicle_type: VehicleTypes,
         reverse: bool,
     ) -> Option<Rc<DirectedVehicleSpecificEdgeInformation>> {
-        self.edge_info.get(&vehicle_type).map(|e| {
-            if reverse ^ self.forward {
-                Rc::clone(&e.directed_info.1)
-            } else {
-                Rc::clone(&e.directed_info.0)
-            }
-        })
+        //the graph contains the edges of every vehicle type, so None is returned when this vehicle type can't use the edge in this direction
+        //edges that are only accessible at certain times are returned, weight calculators decide if they can be used
+        self.edge_info
+            .get(&vehicle_type)
+            .map(|e| {
+                //a reversed edge followed backwards is the original direction again
+                if reverse ^ self.forward {
+                    Rc::clone(&e.directed_info.0)
+                } else {
+                    Rc::clone(&e.directed_info.1)
+                }
+            })
+            .filter(|e| e.is_accessible_sometimes())
     }
 }
 
@@ -109,4 +233,31 @@ impl DirectedVehicleSpecificEdgeInformation {
     pub fn get_distance(&self) -> f64 {
         self.undirected_data.distance
     }
+
+    pub fn get_attributes(&self) -> &EdgeAttributes {
+        &self.undirected_data.attributes
+    }
+
+    //seconds added to the travel time when the edge is used in this direction
+    pub fn get_time_penalty(&self) -> f64 {
+        self.undirected_data.attributes.time_penalty + self.time_penalty
+    }
+
+    //the access without conditional restrictions
+    pub fn is_accessible(&self) -> bool {
+        self.accessible
+    }
+
+    //the access at the given local time, the last condition that holds decides
+    pub fn is_accessible_at(&self, local_time: f64) -> bool {
+        self.conditions
+            .iter()
+            .rev()
+            .find(|(_, hours)| hours.is_open(local_time))
+            .map_or(self.accessible, |(accessible, _)| *accessible)
+    }
+
+    fn is_accessible_sometimes(&self) -> bool {
+        self.accessible || self.conditions.iter().any(|(accessible, _)| *accessible)
+    }
 }
@@ -7,7 +7,7 @@ pub struct EdgeInformation {
     base_node: usize,
     adj_node: usize,
 
-    _edge_info: Rc<DirectedVehicleSpecificEdgeInformation>,
+    edge_info: Rc<DirectedVehicleSpecificEdgeInformation>,
 }
 
 impl EdgeInformation {
@@ -15,7 +15,7 @@ impl EdgeInformation {
         EdgeInformation {
             base_node,
             adj_node,
-            _edge_info: edge_info,
+            edge_info,
         }
     }
 
@@ -26,6 +26,10 @@ impl EdgeInformation {
     pub fn get_adj_node(&self) -> usize {
         self.adj_node
     }
+
+    pub fn get_edge_info(&self) -> &Rc<DirectedVehicleSpecificEdgeInformation> {
+        &self.edge_info
+    }
 }
 
 impl fmt::Debug for EdgeInformation {
@@ -0,0 +1,47 @@
+//small geometric helpers that work on lat/lon coordinates in degrees. All distances are in meters
+
+const EARTH_RADIUS: f64 = 6_371_000.0;
+
+pub fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let delta_lat = (lat2 - lat1).to_radians();
+    let delta_lon = (lon2 - lon1).to_radians();
+
+    let a = (delta_lat / 2.0).sin().powi(2) + lat1.to_radians().cos() * lat2.to_radians().cos() * (delta_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * a.sqrt().asin()
+}
+
+//the closest point on the segment from (lat1, lon1) to (lat2, lon2)
+//this uses an equirectangular projection around the given point, which is accurate enough for short segments
+#[non_exhaustive]
+pub struct Projection {
+    pub lat: f64,
+    pub lon: f64,
+    pub fraction: f64, //0 is the start of the segment, 1 the end
+    pub distance: f64, //distance between the given point and the projected point
+}
+
+pub fn project_on_segment(lat: f64, lon: f64, (lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> Projection {
+    let cos_lat = lat.to_radians().cos();
+    let to_xy = |p_lat: f64, p_lon: f64| ((p_lon - lon) * cos_lat, p_lat - lat); //the given point is the origin
+
+    let (x1, y1) = to_xy(lat1, lon1);
+    let (x2, y2) = to_xy(lat2, lon2);
+    let (dx, dy) = (x2 - x1, y2 - y1);
+
+    let length_squared = dx * dx + dy * dy;
+    let fraction = if length_squared > 0.0 {
+        (-(x1 * dx + y1 * dy) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let proj_lat = lat1 + fraction * (lat2 - lat1);
+    let proj_lon = lon1 + fraction * (lon2 - lon1);
+
+    Projection {
+        lat: proj_lat,
+        lon: proj_lon,
+        fraction,
+        distance: haversine_distance(lat, lon, proj_lat, proj_lon),
+    }
+}
@@ -1,8 +1,10 @@
 use kdtree::KdTree;
 
+use super::components::biconnected::{get_biconnected_components, BiconnectedComponents};
 use super::components::options::ComponentsAlgorithmOptions;
-use super::edge::DirectedVehicleSpecificEdgeInformation;
+use super::components::weakly::get_weakly_connected_components;
 pub use super::edge::Edge;
+use super::edge::{DirectedVehicleSpecificEdgeInformation, VehicleTypes};
 pub use super::node::Node;
 use super::routing::options::RoutingAlgorithmOptions;
 use super::routing::RoutingResult;
@@ -16,14 +18,19 @@ pub trait Graph: std::fmt::Debug {
     //mut functions:
     fn add_node(&mut self, node: Node);
     fn add_edge(&mut self, base_node: usize, adj_node: usize, edge: Edge);
+    //removes both directions of the edge, the nodes stay so that the indices of the other nodes don't change
+    fn remove_edge(&mut self, base_node: usize, adj_node: usize);
+    //the remaining nodes keep their order, with new indices starting from 0
     fn keep_nodes(&mut self, nodes: &HashSet<usize>);
 
     //simple non mut functions
+    //None if there is no edge or if the vehicle type can't use it in this direction
     fn get_directed_vehicle_specific_edge_information(
         &self,
         base_node: usize,
         adj_node: usize,
         reverse: bool,
+        vehicle_type: VehicleTypes,
     ) -> Option<Rc<DirectedVehicleSpecificEdgeInformation>>;
 
     fn get_node(&self, id: usize) -> Option<&Node>;
@@ -46,6 +53,22 @@ pub trait Graph: std::fmt::Debug {
         Self: Sized;
 
     //functions with default implementations
+    //a vector of sets of nodeids, each set is connected when the direction of the edges is ignored
+    fn get_weakly_connected_subgraphs(&self) -> Vec<HashSet<usize>>
+    where
+        Self: Sized,
+    {
+        get_weakly_connected_components(self)
+    }
+
+    //articulation points, bridges and biconnected components, for example to find the roads that would cut off a village when closed
+    fn get_biconnected_components(&self) -> BiconnectedComponents
+    where
+        Self: Sized,
+    {
+        get_biconnected_components(self)
+    }
+
     //used for debugging certain parts of a graph
     fn visualise_sub_graph(&self, nodes: &HashSet<usize>) -> String {
         let mut used = HashSet::new();
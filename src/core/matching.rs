@@ -0,0 +1,252 @@
+use super::edge::VehicleTypes;
+use super::edgeinformation::EdgeInformation;
+use super::geometry::haversine_distance;
+use super::routing::dijkstra::DijkstraRoutingAlgorithm;
+use super::routing::options::{create_vehicle_weight_calculator, WeightType};
+use super::routing::Path;
+use super::spatialindex::EdgeIndex;
+use super::Graph;
+
+use std::rc::Rc;
+
+//hidden markov model map matching, based on "Hidden Markov Map Matching Through Noise and Sparseness" by Newson and Krumm
+
+pub struct GpsPoint {
+    pub lat: f64,
+    pub lon: f64,
+    pub time: Option<f64>, //seconds since the unix epoch
+}
+
+pub struct MapMatchingOptions {
+    pub gps_sigma: f64,        //standard deviation of the gps error in meters
+    pub beta: f64,             //how much the route distance may differ from the straight line distance between 2 points, in meters
+    pub candidate_radius: f64, //only edges within this radius of a gps point are considered
+    pub max_candidates: usize, //per gps point, the closest ones are kept
+    pub vehicle_type: VehicleTypes,
+}
+
+impl Default for MapMatchingOptions {
+    fn default() -> Self {
+        MapMatchingOptions {
+            gps_sigma: 10.0,
+            beta: 5.0,
+            candidate_radius: 50.0,
+            max_candidates: 8,
+            vehicle_type: VehicleTypes::Car,
+        }
+    }
+}
+
+#[non_exhaustive]
+pub struct MapMatchingResult {
+    pub paths: Vec<Path>, //a new path is started when no route could be found between 2 consecutive points
+}
+
+//a possible position of a gps point on a directed edge
+struct Candidate {
+    base_node: usize,
+    adj_node: usize,
+    fraction: f64, //position on the edge
+    edge_distance: f64,
+    log_emission: f64,
+}
+
+/// Match a gps trace onto the graph
+/// the index must be created from the same graph
+pub fn match_trace<G: Graph>(graph: &G, index: &EdgeIndex, points: &[GpsPoint], opts: &MapMatchingOptions) -> MapMatchingResult {
+    let algorithm = DijkstraRoutingAlgorithm {
+        path: false,
+        weight_calculator: create_vehicle_weight_calculator(&WeightType::DISTANCE, opts.vehicle_type),
+    };
+
+    //every step is a gps point together with its candidates, points without candidates are skipped
+    let steps: Vec<(&GpsPoint, Vec<Candidate>)> = filter_points(points, opts)
+        .into_iter()
+        .map(|p| (p, find_candidates(graph, index, p, opts)))
+        .filter(|(_, c)| !c.is_empty())
+        .collect();
+
+    //viterbi, every sequence is a part of the trace that could be matched without interruption
+    let mut sequences: Vec<Vec<&Candidate>> = vec![];
+    let mut log_probabilities: Vec<f64> = vec![];
+    let mut parents: Vec<Vec<Option<usize>>> = vec![];
+    let mut sequence_start = 0;
+
+    for (step, (point, step_candidates)) in steps.iter().enumerate() {
+        if step == sequence_start {
+            log_probabilities = step_candidates.iter().map(|c| c.log_emission).collect();
+            parents = vec![vec![None; step_candidates.len()]];
+            continue;
+        }
+
+        let (previous_point, previous_candidates) = &steps[step - 1];
+        let straight_distance = haversine_distance(previous_point.lat, previous_point.lon, point.lat, point.lon);
+        let max_route_distance = 3.0 * straight_distance + 2.0 * opts.candidate_radius;
+
+        let mut new_log_probabilities = vec![f64::NEG_INFINITY; step_candidates.len()];
+        let mut new_parents = vec![None; step_candidates.len()];
+
+        for (i, from) in previous_candidates.iter().enumerate() {
+            if log_probabilities[i] == f64::NEG_INFINITY {
+                continue;
+            }
+
+            let route_distances = route_distances(graph, &algorithm, from, step_candidates, max_route_distance);
+            for (j, to) in step_candidates.iter().enumerate() {
+                let route_distance = match route_distances[j] {
+                    None => continue,
+                    Some(d) => d,
+                };
+
+                let log_transition = -(route_distance - straight_distance).abs() / opts.beta;
+                let log_probability = log_probabilities[i] + log_transition + to.log_emission;
+                if log_probability > new_log_probabilities[j] {
+                    new_log_probabilities[j] = log_probability;
+                    new_parents[j] = Some(i);
+                }
+            }
+        }
+
+        if new_log_probabilities.iter().all(|p| *p == f64::NEG_INFINITY) {
+            //no transition possible, so finish the current sequence and start a new one from this point
+            sequences.push(backtrack(&steps, &parents, &log_probabilities, sequence_start));
+            sequence_start = step;
+            log_probabilities = step_candidates.iter().map(|c| c.log_emission).collect();
+            parents = vec![vec![None; step_candidates.len()]];
+        } else {
+            log_probabilities = new_log_probabilities;
+            parents.push(new_parents);
+        }
+    }
+
+    if !steps.is_empty() {
+        sequences.push(backtrack(&steps, &parents, &log_probabilities, sequence_start));
+    }
+
+    let routing_algorithm = DijkstraRoutingAlgorithm {
+        path: true,
+        weight_calculator: create_vehicle_weight_calculator(&WeightType::DISTANCE, opts.vehicle_type),
+    };
+    let paths = sequences
+        .iter()
+        .filter_map(|s| create_path(graph, &routing_algorithm, s, opts.vehicle_type))
+        .collect();
+
+    MapMatchingResult { paths }
+}
+
+//points that are too close to the previous point don't add information, so they are removed like in the paper
+fn filter_points<'a>(points: &'a [GpsPoint], opts: &MapMatchingOptions) -> Vec<&'a GpsPoint> {
+    let mut ret: Vec<&GpsPoint> = vec![];
+    for point in points {
+        if let Some(last) = ret.last() {
+            if haversine_distance(last.lat, last.lon, point.lat, point.lon) < 2.0 * opts.gps_sigma {
+                continue;
+            }
+        }
+
+        ret.push(point);
+    }
+
+    ret
+}
+
+fn find_candidates<G: Graph>(graph: &G, index: &EdgeIndex, point: &GpsPoint, opts: &MapMatchingOptions) -> Vec<Candidate> {
+    let mut candidates = vec![];
+    for hit in index.within_radius(point.lat, point.lon, opts.candidate_radius) {
+        //the index doesn't know the direction of the edges, so try both
+        for (base_node, adj_node, fraction) in [
+            (hit.node1, hit.node2, hit.projection.fraction),
+            (hit.node2, hit.node1, 1.0 - hit.projection.fraction),
+        ] {
+            if let Some(edge_info) = graph.get_directed_vehicle_specific_edge_information(base_node, adj_node, false, opts.vehicle_type) {
+                candidates.push(Candidate {
+                    base_node,
+                    adj_node,
+                    fraction,
+                    edge_distance: edge_info.get_distance(),
+                    log_emission: -0.5 * (hit.projection.distance / opts.gps_sigma).powi(2),
+                });
+            }
+        }
+    }
+
+    //within_radius is sorted by distance already
+    candidates.truncate(opts.max_candidates);
+    candidates
+}
+
+//distance over the graph between from and every candidate in to
+fn route_distances<G: Graph>(
+    graph: &G,
+    algorithm: &DijkstraRoutingAlgorithm,
+    from: &Candidate,
+    to: &[Candidate],
+    max_distance: f64,
+) -> Vec<Option<f64>> {
+    let remaining_from = (1.0 - from.fraction) * from.edge_distance;
+    let targets: Vec<usize> = to.iter().map(|c| c.base_node).collect();
+    let results = algorithm.route_one_to_many(graph, from.adj_node, &targets, max_distance);
+
+    to.iter()
+        .zip(results)
+        .map(|(candidate, result)| {
+            if candidate.base_node == from.base_node && candidate.adj_node == from.adj_node && candidate.fraction >= from.fraction {
+                //still on the same edge
+                return Some((candidate.fraction - from.fraction) * from.edge_distance);
+            }
+
+            result.map(|r| remaining_from + r.weight + candidate.fraction * candidate.edge_distance)
+        })
+        .collect()
+}
+
+fn backtrack<'a>(
+    steps: &'a [(&GpsPoint, Vec<Candidate>)],
+    parents: &[Vec<Option<usize>>],
+    log_probabilities: &[f64],
+    sequence_start: usize,
+) -> Vec<&'a Candidate> {
+    let mut best = (0..log_probabilities.len())
+        .max_by(|a, b| log_probabilities[*a].total_cmp(&log_probabilities[*b]))
+        .unwrap(); //there is always at least one candidate per step
+
+    let mut ret = vec![];
+    for (offset, step_parents) in parents.iter().enumerate().rev() {
+        ret.push(&steps[sequence_start + offset].1[best]);
+        if let Some(parent) = step_parents[best] {
+            best = parent;
+        }
+    }
+
+    ret.reverse();
+    ret
+}
+
+fn create_path<G: Graph>(graph: &G, algorithm: &DijkstraRoutingAlgorithm, sequence: &[&Candidate], vehicle_type: VehicleTypes) -> Option<Path> {
+    let first = sequence.first()?;
+    let mut path = Path::new(vec![create_edge_information(graph, first, vehicle_type)?]);
+
+    for pair in sequence.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        if from.base_node == to.base_node && from.adj_node == to.adj_node && to.fraction >= from.fraction {
+            continue;
+        }
+
+        if from.adj_node != to.base_node {
+            let mut result = algorithm
+                .route_one_to_many(graph, from.adj_node, &[to.base_node], f64::INFINITY)
+                .pop()??;
+            path.add_edges(result.paths.pop()?.get_edges().to_vec());
+        }
+
+        path.add_edge(create_edge_information(graph, to, vehicle_type)?);
+    }
+
+    Some(path)
+}
+
+fn create_edge_information<G: Graph>(graph: &G, candidate: &Candidate, vehicle_type: VehicleTypes) -> Option<Rc<EdgeInformation>> {
+    let edge_info = graph.get_directed_vehicle_specific_edge_information(candidate.base_node, candidate.adj_node, false, vehicle_type)?;
+    Some(Rc::new(EdgeInformation::new(candidate.base_node, candidate.adj_node, edge_info)))
+}
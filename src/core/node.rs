@@ -9,6 +9,17 @@ impl Node {
     pub fn new(osm_id: i64, lat: f64, lon: f64) -> Self {
         Node { osm_id, lat, lon }
     }
+
+    //initial compass bearing in degrees (0 = north, 90 = east) when travelling from this node to the other node
+    pub fn bearing_to(&self, other: &Node) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let delta_lon = (other.lon - self.lon).to_radians();
+
+        let y = delta_lon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * delta_lon.cos();
+
+        (y.atan2(x).to_degrees() + 360.0) % 360.0
+    }
 }
 
 impl Default for Node {
@@ -50,7 +50,7 @@ impl Path {
     }
 
     pub fn get_wkt(&self, graph: &impl Graph) -> String {
-        let mut first = false;
+        let mut first = true;
         let res = self.edges.iter().fold(vec![], |mut acc, e| {
             if first {
                 let base_node = e.get_base_node();
@@ -71,6 +71,30 @@ impl Path {
         format!("LINESTRING({})", res.join(","))
     }
 
+    pub fn get_edges(&self) -> &[Rc<EdgeInformation>] {
+        &self.edges
+    }
+
+    //sum of the edge lengths, independent of the weight that was used to find this path
+    pub fn get_distance(&self) -> f64 {
+        self.edges.iter().map(|e| e.get_edge_info().get_distance()).sum()
+    }
+
+    pub fn get_time(&self) -> f64 {
+        self.edges
+            .iter()
+            .map(|e| {
+                let edge_info = e.get_edge_info();
+                let speed = edge_info.get_speed();
+                if speed > 0.0 {
+                    edge_info.get_distance() / speed
+                } else {
+                    f64::INFINITY
+                }
+            })
+            .sum()
+    }
+
     pub fn get_nodes(&self) -> Vec<usize> {
         let mut ret = vec![];
         let mut start = true;
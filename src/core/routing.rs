@@ -4,6 +4,9 @@ pub mod dijkstra2;
 pub mod heapentry;
 pub mod heapentry2;
 pub mod options;
+pub mod tddijkstra;
+pub mod trip;
+pub mod waypoints;
 
 pub use super::path::Path;
 
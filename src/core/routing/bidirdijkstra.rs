@@ -7,6 +7,7 @@ use super::heapentry::*;
 use super::options::RoutingAlgorithm;
 use super::Path;
 use super::RoutingResult;
+use crate::core::edge::VehicleTypes;
 use crate::core::Graph;
 use crate::core::WeightCalculator;
 
@@ -136,12 +137,16 @@ impl BidirDijkstraRoutingAlgorithm {
 
             graph.do_for_all_neighbors(index, reverse, |adj_node| {
                 if !data.used.contains(&adj_node) {
-                    let directed_edge_info = graph.get_directed_vehicle_specific_edge_information(index, adj_node, reverse).unwrap();
+                    let vehicle_type = self.weight_calculator.get_vehicle_type();
+                    let directed_edge_info = match graph.get_directed_vehicle_specific_edge_information(index, adj_node, reverse, vehicle_type) {
+                        None => return, //this vehicle type can't use the edge
+                        Some(e) => e,
+                    };
 
                     //if dist(start->index) + dist(index->adj_node) < dist(start->adj_node)
                     let dist1 = *data.distances.get(&index).map_or(&f64::INFINITY, |heap_entry| &heap_entry.key);
 
-                    let weight = &self.weight_calculator.calc_weight(&directed_edge_info);
+                    let weight = &self.weight_calculator.calc_weight(index, adj_node, &directed_edge_info);
 
                     let mut create_new_heap_entry = || {
                         let mut parent = None;
@@ -210,13 +215,31 @@ impl<G: Graph> RoutingAlgorithm<G> for BidirDijkstraRoutingAlgorithm {
             Some(RoutingResult {
                 distance: data.best.weight,
                 weight: data.best.weight,
-                paths: vec![extract_path(graph, data.best.fwd_entry, data.best.bwd_entry, start, end)],
+                paths: vec![extract_path(
+                    graph,
+                    self.weight_calculator.get_vehicle_type(),
+                    data.best.fwd_entry,
+                    data.best.bwd_entry,
+                    start,
+                    end,
+                )],
             })
         }
     }
+
+    fn get_weight_calculator(&self) -> Option<&WeightCalculator> {
+        Some(&self.weight_calculator)
+    }
 }
 
-fn extract_path(graph: &impl Graph, fwd: Option<Rc<HeapEntry>>, bwd: Option<Rc<HeapEntry>>, start: usize, end: usize) -> Path {
+fn extract_path(
+    graph: &impl Graph,
+    vehicle_type: VehicleTypes,
+    fwd: Option<Rc<HeapEntry>>,
+    bwd: Option<Rc<HeapEntry>>,
+    start: usize,
+    end: usize,
+) -> Path {
     let (fwd_edges, fwd_last_node) = match fwd {
         None => (vec![], start),
         Some(fwd_entry) => (fwd_entry.get_path(true), fwd_entry.value),
@@ -229,7 +252,7 @@ fn extract_path(graph: &impl Graph, fwd: Option<Rc<HeapEntry>>, bwd: Option<Rc<H
     };
 
     if fwd_last_node != bwd_first_node {
-        let edge_info_option = graph.get_directed_vehicle_specific_edge_information(fwd_last_node, bwd_first_node, false);
+        let edge_info_option = graph.get_directed_vehicle_specific_edge_information(fwd_last_node, bwd_first_node, false, vehicle_type);
         if let Some(edge_info) = edge_info_option {
             let middle_edge = Rc::new(EdgeInformation::new(fwd_last_node, bwd_first_node, edge_info));
             path.add_edge(middle_edge);
@@ -20,7 +20,6 @@ struct AlgorithmData {
     distances: HashMap<usize, f64>,
     used: HashSet<usize>,
     heap: BinaryHeap<Rc<HeapEntry>>,
-    current_heap_entry: Rc<HeapEntry>,
 }
 
 impl AlgorithmData {
@@ -31,48 +30,132 @@ impl AlgorithmData {
         let used = HashSet::new();
         let mut heap = BinaryHeap::new();
 
-        let current_heap_entry = Rc::new(HeapEntry::new(0.0, start, None, None));
-        heap.push(Rc::clone(&current_heap_entry));
+        heap.push(Rc::new(HeapEntry::new(0.0, start, None, None)));
 
-        AlgorithmData {
-            distances,
-            used,
-            heap,
-            current_heap_entry,
-        }
+        AlgorithmData { distances, used, heap }
     }
 }
 
-impl<G: Graph> RoutingAlgorithm<G> for DijkstraRoutingAlgorithm {
-    fn route(&self, graph: &G, start: usize, end: usize) -> Option<RoutingResult> {
+impl DijkstraRoutingAlgorithm {
+    /// Route from start to every target with a single search
+    /// the results are in the same order as the targets, None if the target can't be reached within max_weight
+    pub fn route_one_to_many(&self, graph: &impl Graph, start: usize, targets: &[usize], max_weight: f64) -> Vec<Option<RoutingResult>> {
+        let mut remaining: HashSet<usize> = targets.iter().copied().collect();
+        let mut settled = HashMap::new();
+
+        self.explore(graph, start, false, max_weight, |heap_entry| {
+            if remaining.remove(&heap_entry.value) {
+                settled.insert(heap_entry.value, Rc::clone(heap_entry));
+            }
+
+            remaining.is_empty()
+        });
+
+        targets
+            .iter()
+            .map(|target| settled.get(target).map(|e| create_routing_result(e, false)))
+            .collect()
+    }
+
+    /// The k targets closest to start, sorted by weight
+    /// every hit is the index of the target in targets together with the route to it. The search stops once k targets are settled
+    pub fn route_k_closest(&self, graph: &impl Graph, start: usize, targets: &[usize], k: usize, max_weight: f64) -> Vec<(usize, RoutingResult)> {
+        let mut target_indexes: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (i, target) in targets.iter().enumerate() {
+            target_indexes.entry(*target).or_default().push(i);
+        }
+
+        let mut hits = vec![];
+        if k == 0 {
+            return hits;
+        }
+
+        self.explore(graph, start, false, max_weight, |heap_entry| {
+            //the same node can be given more than once, every occurrence counts as a hit
+            for i in target_indexes.remove(&heap_entry.value).into_iter().flatten() {
+                if hits.len() < k {
+                    hits.push((i, create_routing_result(heap_entry, false)));
+                }
+            }
+
+            hits.len() >= k
+        });
+
+        hits
+    }
+
+    /// Route from every source to end with a single backward search
+    /// the results are in the same order as the sources, None if end can't be reached from the source within max_weight
+    pub fn route_many_to_one(&self, graph: &impl Graph, sources: &[usize], end: usize, max_weight: f64) -> Vec<Option<RoutingResult>> {
+        let mut remaining: HashSet<usize> = sources.iter().copied().collect();
+        let mut settled = HashMap::new();
+
+        self.explore(graph, end, true, max_weight, |heap_entry| {
+            if remaining.remove(&heap_entry.value) {
+                settled.insert(heap_entry.value, Rc::clone(heap_entry));
+            }
+
+            remaining.is_empty()
+        });
+
+        sources
+            .iter()
+            .map(|source| settled.get(source).map(|e| create_routing_result(e, true)))
+            .collect()
+    }
+
+    /// The weight from every node that can reach end within max_weight, to end
+    pub fn route_all_to_one(&self, graph: &impl Graph, end: usize, max_weight: f64) -> HashMap<usize, f64> {
+        let mut weights = HashMap::new();
+
+        self.explore(graph, end, true, max_weight, |heap_entry| {
+            weights.insert(heap_entry.value, *heap_entry.key);
+            false
+        });
+
+        weights
+    }
+
+    //settles nodes in order of their weight until f returns true, or until there are no nodes left within max_weight
+    //when reverse is true the edges are followed backwards, so the weights are the weights to start instead of from start
+    fn explore<F>(&self, graph: &impl Graph, start: usize, reverse: bool, max_weight: f64, mut f: F)
+    where
+        F: FnMut(&Rc<HeapEntry>) -> bool,
+    {
         let AlgorithmData {
             mut distances,
             mut used,
             mut heap,
-            mut current_heap_entry,
         } = AlgorithmData::new(start);
 
-        while !heap.is_empty() && !used.contains(&end) {
-            current_heap_entry = heap.pop().unwrap(); //OK because of is_empty check above
+        while let Some(current_heap_entry) = heap.pop() {
             let index = current_heap_entry.value;
 
+            if *current_heap_entry.key > max_weight {
+                break;
+            }
+
             //Since we are only pushing on the heap entry and not updating existing values
             //it is possible that we do the same node twice. But theres no point in redoing a settled node
             if !used.insert(index) {
                 continue;
             }
 
-            if index == end {
+            if f(&current_heap_entry) {
                 break;
             }
 
-            graph.do_for_all_neighbors(index, false, |adj_node| {
-                let directed_edge_info = graph.get_directed_vehicle_specific_edge_information(index, adj_node, false).unwrap();
+            graph.do_for_all_neighbors(index, reverse, |adj_node| {
+                let vehicle_type = self.weight_calculator.get_vehicle_type();
+                let directed_edge_info = match graph.get_directed_vehicle_specific_edge_information(index, adj_node, reverse, vehicle_type) {
+                    None => return, //this vehicle type can't use the edge
+                    Some(e) => e,
+                };
 
                 if !used.contains(&adj_node) {
                     //if dist(start->index) + dist(index->adj_node) < dist(start->adj_node)
                     let dist1 = *distances.get(&index).unwrap_or(&f64::INFINITY);
-                    let weight = &self.weight_calculator.calc_weight(&directed_edge_info);
+                    let weight = &self.weight_calculator.calc_weight(index, adj_node, &directed_edge_info);
                     let dist2 = distances.entry(adj_node).or_insert(f64::INFINITY);
                     if dist1 + weight < *dist2 {
                         *dist2 = dist1 + weight;
@@ -81,7 +164,7 @@ impl<G: Graph> RoutingAlgorithm<G> for DijkstraRoutingAlgorithm {
                         let mut edge_info = None;
                         if self.path {
                             parent = Some(Rc::clone(&current_heap_entry));
-                            edge_info = create_edge_information(directed_edge_info, index, adj_node, false);
+                            edge_info = create_edge_information(directed_edge_info, index, adj_node, reverse);
                         }
                         let new_heap_entry = Rc::new(HeapEntry::new(*dist2, adj_node, edge_info, parent));
                         heap.push(new_heap_entry);
@@ -89,15 +172,35 @@ impl<G: Graph> RoutingAlgorithm<G> for DijkstraRoutingAlgorithm {
                 }
             });
         }
+    }
+}
 
-        if !used.contains(&end) {
-            None
-        } else {
-            Some(RoutingResult {
-                distance: *current_heap_entry.key,
-                weight: *current_heap_entry.key,
-                paths: vec![Path::new(current_heap_entry.get_path(true))],
-            })
-        }
+impl<G: Graph> RoutingAlgorithm<G> for DijkstraRoutingAlgorithm {
+    fn route(&self, graph: &G, start: usize, end: usize) -> Option<RoutingResult> {
+        let mut result = None;
+
+        self.explore(graph, start, false, f64::INFINITY, |heap_entry| {
+            if heap_entry.value == end {
+                result = Some(create_routing_result(heap_entry, false));
+                return true;
+            }
+
+            false
+        });
+
+        result
+    }
+
+    fn get_weight_calculator(&self) -> Option<&WeightCalculator> {
+        Some(&self.weight_calculator)
+    }
+}
+
+//the path of a backward search already starts at the heap entry, so it doesn't have to be reversed
+fn create_routing_result(heap_entry: &Rc<HeapEntry>, reverse: bool) -> RoutingResult {
+    RoutingResult {
+        distance: *heap_entry.key,
+        weight: *heap_entry.key,
+        paths: vec![Path::new(heap_entry.get_path(!reverse))],
     }
 }
@@ -61,7 +61,11 @@ impl<G: Graph> RoutingAlgorithm<G> for DijkstraRoutingAlgorithm2 {
             }
 
             graph.do_for_all_neighbors(index, false, |adj_node| {
-                let directed_edge_info = graph.get_directed_vehicle_specific_edge_information(index, adj_node, false).unwrap();
+                let vehicle_type = self.weight_calculator.get_vehicle_type();
+                let directed_edge_info = match graph.get_directed_vehicle_specific_edge_information(index, adj_node, false, vehicle_type) {
+                    None => return, //this vehicle type can't use the edge
+                    Some(e) => e,
+                };
 
                 let adj_heap_entry = distances.get(&adj_node);
 
@@ -70,7 +74,11 @@ impl<G: Graph> RoutingAlgorithm<G> for DijkstraRoutingAlgorithm2 {
                     parent = Some(Rc::clone(&current_heap_entry));
                 }
 
-                let weight = &self.weight_calculator.calc_weight(&directed_edge_info);
+                let weight = &self.weight_calculator.calc_weight(index, adj_node, &directed_edge_info);
+                if weight.is_infinite() {
+                    return; //the edge can't be used right now
+                }
+
                 let dist2 = *current_heap_entry_borrowed.key + weight;
                 match adj_heap_entry {
                     None => {
@@ -89,7 +97,7 @@ impl<G: Graph> RoutingAlgorithm<G> for DijkstraRoutingAlgorithm2 {
             });
         }
 
-        if !distances.contains_key(&end) {
+        if !distances.contains_key(&end) || current_heap_entry.borrow().key.is_infinite() {
             None
         } else {
             Some(RoutingResult {
@@ -99,4 +107,8 @@ impl<G: Graph> RoutingAlgorithm<G> for DijkstraRoutingAlgorithm2 {
             })
         }
     }
+
+    fn get_weight_calculator(&self) -> Option<&WeightCalculator> {
+        Some(&self.weight_calculator)
+    }
 }
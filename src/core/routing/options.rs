@@ -1,7 +1,12 @@
 use super::{bidirdijkstra::BidirDijkstraRoutingAlgorithm, dijkstra::DijkstraRoutingAlgorithm, dijkstra2::DijkstraRoutingAlgorithm2, RoutingResult};
-use crate::core::weight::{DistanceWeight, TravelTimeWeight};
+use crate::core::edge::VehicleTypes;
+use crate::core::traffic::TrafficOverlay;
+use crate::core::weight::{
+    ConditionalWeight, CustomRule, CustomWeight, DimensionsWeight, DistanceWeight, Traffic, TravelTimeWeight, VehicleDimensions,
+};
 use crate::core::Graph;
 use crate::core::WeightCalculator;
+use std::rc::Rc;
 
 #[non_exhaustive]
 pub enum AlgorithmType {
@@ -20,6 +25,11 @@ pub enum WeightType {
 /// start and end must be a valid node within graph
 pub trait RoutingAlgorithm<G: Graph> {
     fn route(&self, graph: &G, start: usize, end: usize) -> Option<RoutingResult>;
+
+    //the weights of single edges, for the legs of a route via waypoints. Algorithms without it can't route between adjacent waypoints
+    fn get_weight_calculator(&self) -> Option<&WeightCalculator> {
+        None
+    }
 }
 
 pub struct RoutingAlgorithmOptions<G: Graph> {
@@ -40,18 +50,68 @@ impl<G: Graph> RoutingAlgorithmOptions<G> {
             routing_algorithm,
         }
     }
+
+    pub fn with_weight_calculator(path: bool, algorithm_type: AlgorithmType, weight_calculator: WeightCalculator) -> Self {
+        RoutingAlgorithmOptions {
+            routing_algorithm: create_routing_algorithm_with_weight_calculator(path, &algorithm_type, weight_calculator),
+        }
+    }
 }
 
+//weights for cars
 pub fn create_weight_calculator(weight_type: &WeightType) -> WeightCalculator {
+    create_vehicle_weight_calculator(weight_type, VehicleTypes::Car)
+}
+
+pub fn create_vehicle_weight_calculator(weight_type: &WeightType, vehicle_type: VehicleTypes) -> WeightCalculator {
     match weight_type {
-        WeightType::DISTANCE => WeightCalculator::TravelTime(TravelTimeWeight {}),
-        WeightType::TRAVELTIME => WeightCalculator::Distance(DistanceWeight {}),
+        WeightType::DISTANCE => WeightCalculator::Distance(DistanceWeight { vehicle_type }),
+        WeightType::TRAVELTIME => WeightCalculator::TravelTime(TravelTimeWeight { vehicle_type, traffic: None }),
     }
 }
 
+//travel time where the speeds of the overlay replace the speeds of the graph
+pub fn create_traffic_weight_calculator(vehicle_type: VehicleTypes, overlay: Rc<TrafficOverlay>, time: f64) -> WeightCalculator {
+    WeightCalculator::TravelTime(TravelTimeWeight {
+        vehicle_type,
+        traffic: Some(Traffic { overlay, time }),
+    })
+}
+
+//applies the conditional restrictions of the edges at the given local time
+pub fn create_conditional_weight_calculator(weight_calculator: WeightCalculator, local_time: f64) -> WeightCalculator {
+    WeightCalculator::Conditional(ConditionalWeight {
+        weight_calculator: Box::new(weight_calculator),
+        local_time,
+    })
+}
+
+//excludes the edges whose weight, size or hazmat limits the vehicle doesn't meet
+pub fn create_dimensions_weight_calculator(weight_calculator: WeightCalculator, dimensions: VehicleDimensions) -> WeightCalculator {
+    WeightCalculator::Dimensions(DimensionsWeight {
+        weight_calculator: Box::new(weight_calculator),
+        dimensions,
+    })
+}
+
+pub fn create_custom_weight_calculator(weight_type: &WeightType, vehicle_type: VehicleTypes, rules: Vec<CustomRule>) -> WeightCalculator {
+    WeightCalculator::Custom(CustomWeight {
+        vehicle_type,
+        travel_time: matches!(weight_type, WeightType::TRAVELTIME),
+        rules,
+    })
+}
+
 // Creates a routing algorithm based on the given algorithm options
 pub fn create_routing_algorithm<G: Graph>(path: bool, algorithm_type: &AlgorithmType, weight_type: &WeightType) -> Box<dyn RoutingAlgorithm<G>> {
-    let weight_calculator = create_weight_calculator(weight_type);
+    create_routing_algorithm_with_weight_calculator(path, algorithm_type, create_weight_calculator(weight_type))
+}
+
+pub fn create_routing_algorithm_with_weight_calculator<G: Graph>(
+    path: bool,
+    algorithm_type: &AlgorithmType,
+    weight_calculator: WeightCalculator,
+) -> Box<dyn RoutingAlgorithm<G>> {
     match algorithm_type {
         AlgorithmType::DIJKSTRA => Box::new(DijkstraRoutingAlgorithm { path, weight_calculator }),
         AlgorithmType::DIJKSTRA2 => Box::new(DijkstraRoutingAlgorithm2 { path, weight_calculator }),
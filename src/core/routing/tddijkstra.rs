@@ -0,0 +1,96 @@
+use super::heapentry::*;
+use super::options::RoutingAlgorithm;
+use super::Path;
+use super::RoutingResult;
+use crate::core::edge::DirectedVehicleSpecificEdgeInformation;
+use crate::core::traffic::SpeedProfiles;
+use crate::core::Graph;
+use crate::core::WeightCalculator;
+
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+//dijkstra where the travel time of an edge depends on the time the edge is entered
+//this is correct because the travel times of the speed profiles are FIFO
+pub struct TimeDependentDijkstraRoutingAlgorithm {
+    pub path: bool,
+    pub weight_calculator: WeightCalculator, //for edges without a speed profile, this should be travel time based
+    pub speed_profiles: Rc<SpeedProfiles>,
+    pub departure: f64,  //seconds since the unix epoch
+    pub utc_offset: f64, //in seconds, conditional restrictions are in local time
+}
+
+impl TimeDependentDijkstraRoutingAlgorithm {
+    //seconds needed for the edge when entering it at time, conditional restrictions are checked at that time as well
+    fn calc_travel_time(&self, base_node: usize, adj_node: usize, edge: &Rc<DirectedVehicleSpecificEdgeInformation>, time: f64) -> f64 {
+        let accessible = edge.is_accessible_at(time + self.utc_offset);
+        match self.speed_profiles.get_profile(edge.get_attributes().way_id, base_node, adj_node) {
+            Some(_) if !accessible => f64::INFINITY,
+            Some(profile) => profile.travel_time(edge.get_distance(), time) + edge.get_time_penalty(),
+            None => self.weight_calculator.calc_weight_with_access(base_node, adj_node, edge, accessible),
+        }
+    }
+}
+
+impl<G: Graph> RoutingAlgorithm<G> for TimeDependentDijkstraRoutingAlgorithm {
+    /// The weight of the result is the travel time in seconds
+    fn route(&self, graph: &G, start: usize, end: usize) -> Option<RoutingResult> {
+        let vehicle_type = self.weight_calculator.get_vehicle_type();
+
+        let mut travel_times: HashMap<usize, f64> = HashMap::new();
+        let mut used = HashSet::new();
+        let mut heap = BinaryHeap::new();
+
+        travel_times.insert(start, 0.0);
+        heap.push(Rc::new(HeapEntry::new(0.0, start, None, None)));
+
+        while let Some(current_heap_entry) = heap.pop() {
+            let index = current_heap_entry.value;
+            if !used.insert(index) {
+                continue;
+            }
+
+            if index == end {
+                return Some(RoutingResult {
+                    distance: *current_heap_entry.key,
+                    weight: *current_heap_entry.key,
+                    paths: vec![Path::new(current_heap_entry.get_path(true))],
+                });
+            }
+
+            let time = self.departure + *current_heap_entry.key;
+            graph.do_for_all_neighbors(index, false, |adj_node| {
+                if used.contains(&adj_node) {
+                    return;
+                }
+
+                let directed_edge_info = match graph.get_directed_vehicle_specific_edge_information(index, adj_node, false, vehicle_type) {
+                    None => return, //this vehicle type can't use the edge
+                    Some(e) => e,
+                };
+
+                let travel_time = *current_heap_entry.key + self.calc_travel_time(index, adj_node, &directed_edge_info, time);
+                let best = travel_times.entry(adj_node).or_insert(f64::INFINITY);
+                if travel_time < *best {
+                    *best = travel_time;
+
+                    let mut parent = None;
+                    let mut edge_info = None;
+                    if self.path {
+                        parent = Some(Rc::clone(&current_heap_entry));
+                        edge_info = create_edge_information(directed_edge_info, index, adj_node, false);
+                    }
+                    heap.push(Rc::new(HeapEntry::new(travel_time, adj_node, edge_info, parent)));
+                }
+            });
+        }
+
+        None
+    }
+
+    fn get_weight_calculator(&self) -> Option<&WeightCalculator> {
+        Some(&self.weight_calculator)
+    }
+}
@@ -0,0 +1,190 @@
+use super::dijkstra::DijkstraRoutingAlgorithm;
+use super::options::{create_routing_algorithm, create_weight_calculator, AlgorithmType, RoutingAlgorithmOptions, WeightType};
+use super::waypoints::{self, MultiLegRoutingResult, WaypointOptions};
+use super::Path;
+use crate::core::Graph;
+
+//the first waypoint is always the start of the trip
+pub struct TripOptions {
+    pub round_trip: bool, //return to the start after visiting all waypoints
+    pub fixed_end: bool,  //the last waypoint has to be visited last. Ignored for round trips
+}
+
+impl Default for TripOptions {
+    fn default() -> Self {
+        TripOptions {
+            round_trip: true,
+            fixed_end: false,
+        }
+    }
+}
+
+#[non_exhaustive]
+pub struct TripResult {
+    pub order: Vec<usize>, //indexes in the given waypoints, in visiting order. For round trips the start isn't repeated at the end
+    pub route: MultiLegRoutingResult,
+}
+
+/// Find a short trip visiting all waypoints and plan the route along it
+/// this uses a heuristic so the result is not guaranteed to be optimal
+/// None without waypoints, a single waypoint is a trip without legs
+pub fn optimize_trip<G: Graph>(graph: &G, weight_type: &WeightType, waypoints: &[usize], trip_opts: &TripOptions) -> Option<TripResult> {
+    match waypoints.len() {
+        0 => return None,
+        1 => {
+            let route = MultiLegRoutingResult {
+                legs: vec![],
+                path: Path::new(vec![]),
+            };
+            return Some(TripResult { order: vec![0], route });
+        }
+        _ => {}
+    }
+
+    let matrix = create_weight_matrix(graph, weight_type, waypoints);
+    let order = optimize_order(&matrix, trip_opts);
+
+    let mut ordered_waypoints: Vec<usize> = order.iter().map(|i| waypoints[*i]).collect();
+    if trip_opts.round_trip {
+        ordered_waypoints.push(waypoints[0]);
+    }
+
+    let opts = RoutingAlgorithmOptions {
+        routing_algorithm: create_routing_algorithm(true, &AlgorithmType::BIDIRDIJKSTRA, weight_type),
+    };
+    let route = waypoints::route_via(graph, &opts, &ordered_waypoints, &WaypointOptions::default())?;
+
+    Some(TripResult { order, route })
+}
+
+//matrix[i][j] is the weight from waypoint i to waypoint j, infinite if j can't be reached from i
+pub fn create_weight_matrix<G: Graph>(graph: &G, weight_type: &WeightType, waypoints: &[usize]) -> Vec<Vec<f64>> {
+    let algorithm = DijkstraRoutingAlgorithm {
+        path: false,
+        weight_calculator: create_weight_calculator(weight_type),
+    };
+
+    waypoints
+        .iter()
+        .map(|start| {
+            algorithm
+                .route_one_to_many(graph, *start, waypoints, f64::INFINITY)
+                .iter()
+                .map(|result| result.as_ref().map_or(f64::INFINITY, |r| r.weight))
+                .collect()
+        })
+        .collect()
+}
+
+/// Order the waypoints with nearest neighbour, then improve the order with 2-opt and or-opt until neither finds an improvement
+/// the matrix doesn't need to be symmetric
+pub fn optimize_order(matrix: &[Vec<f64>], trip_opts: &TripOptions) -> Vec<usize> {
+    let n = matrix.len();
+    if n <= 2 {
+        return (0..n).collect();
+    }
+
+    let fixed_end = trip_opts.fixed_end && !trip_opts.round_trip;
+    let mut order = nearest_neighbor_order(matrix, fixed_end);
+
+    //the positions in [first, last) can be moved around
+    let (first, last) = (1, if fixed_end { n - 1 } else { n });
+
+    let mut improved = true;
+    while improved {
+        improved = two_opt(matrix, &mut order, first, last, trip_opts.round_trip);
+        improved |= or_opt(matrix, &mut order, first, last, trip_opts.round_trip);
+    }
+
+    order
+}
+
+fn nearest_neighbor_order(matrix: &[Vec<f64>], fixed_end: bool) -> Vec<usize> {
+    let n = matrix.len();
+    let mut visited = vec![false; n];
+    visited[0] = true;
+    if fixed_end {
+        visited[n - 1] = true;
+    }
+
+    let mut order = vec![0];
+    let mut current = 0;
+    while let Some(next) = (0..n)
+        .filter(|i| !visited[*i])
+        .min_by(|a, b| matrix[current][*a].total_cmp(&matrix[current][*b]))
+    {
+        visited[next] = true;
+        order.push(next);
+        current = next;
+    }
+
+    if fixed_end {
+        order.push(n - 1);
+    }
+
+    order
+}
+
+fn tour_weight(matrix: &[Vec<f64>], order: &[usize], round_trip: bool) -> f64 {
+    let mut weight: f64 = order.windows(2).map(|w| matrix[w[0]][w[1]]).sum();
+    if round_trip {
+        weight += matrix[*order.last().unwrap()][order[0]];
+    }
+
+    weight
+}
+
+//reverse a part of the tour. The whole tour is recalculated because reversing changes the weight of every edge in an asymmetric matrix
+fn two_opt(matrix: &[Vec<f64>], order: &mut [usize], first: usize, last: usize, round_trip: bool) -> bool {
+    let mut improved = false;
+    let mut best_weight = tour_weight(matrix, order, round_trip);
+
+    for i in first..last {
+        for j in i + 1..last {
+            order[i..=j].reverse();
+
+            let weight = tour_weight(matrix, order, round_trip);
+            if weight < best_weight {
+                best_weight = weight;
+                improved = true;
+            } else {
+                order[i..=j].reverse();
+            }
+        }
+    }
+
+    improved
+}
+
+//move a segment of up to three waypoints to another place in the tour
+fn or_opt(matrix: &[Vec<f64>], order: &mut Vec<usize>, first: usize, last: usize, round_trip: bool) -> bool {
+    let mut improved = false;
+    let mut best_weight = tour_weight(matrix, order, round_trip);
+
+    for segment_length in 1..=3 {
+        for i in first..last {
+            if i + segment_length > last {
+                break;
+            }
+
+            for j in first..=last - segment_length {
+                if j == i {
+                    continue;
+                }
+
+                let mut candidate = order.clone();
+                let segment: Vec<usize> = candidate.drain(i..i + segment_length).collect();
+                candidate.splice(j..j, segment);
+
+                let weight = tour_weight(matrix, &candidate, round_trip);
+                if weight < best_weight {
+                    best_weight = weight;
+                    *order = candidate;
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    improved
+}
@@ -0,0 +1,202 @@
+use super::options::RoutingAlgorithmOptions;
+use super::Path;
+use crate::core::edgeinformation::EdgeInformation;
+use crate::core::Graph;
+
+use std::rc::Rc;
+
+pub struct WaypointOptions {
+    pub pass_through: bool,         //don't allow to turn around at a via point
+    pub headings: Vec<Option<f64>>, //in degrees (0 = north), one per waypoint. Can be shorter than the waypoints
+    pub heading_tolerance: f64,     //max deviation from the heading in degrees
+}
+
+impl Default for WaypointOptions {
+    fn default() -> Self {
+        WaypointOptions {
+            pass_through: false,
+            headings: vec![],
+            heading_tolerance: 45.0,
+        }
+    }
+}
+
+#[non_exhaustive]
+pub struct LegResult {
+    pub distance: f64,
+    pub time: f64,
+    pub weight: f64,
+}
+
+#[non_exhaustive]
+pub struct MultiLegRoutingResult {
+    pub legs: Vec<LegResult>,
+    pub path: Path, //combined path of all legs
+}
+
+//restrictions on the first and last edge of a single leg
+struct LegConstraints {
+    excluded_first_node: Option<usize>,
+    departure_heading: Option<f64>,
+    arrival_heading: Option<f64>,
+    heading_tolerance: f64,
+}
+
+/// Plan a route that visits all waypoints in the given order
+/// opts must keep track of the path, otherwise the legs can't be combined
+/// the heading of a waypoint is used as departure direction, except for the last waypoint where it is the arrival direction
+pub fn route_via<G: Graph>(
+    graph: &G,
+    opts: &RoutingAlgorithmOptions<G>,
+    waypoints: &[usize],
+    waypoint_opts: &WaypointOptions,
+) -> Option<MultiLegRoutingResult> {
+    if waypoints.len() < 2 {
+        return None;
+    }
+
+    let heading = |i: usize| waypoint_opts.headings.get(i).copied().flatten();
+
+    let mut legs = vec![];
+    let mut path = Path::new(vec![]);
+    for i in 0..waypoints.len() - 1 {
+        let is_last_leg = i == waypoints.len() - 2;
+
+        //the node we came from before arriving at the via point
+        let previous_node = path.get_edges().last().map(|e| e.get_base_node());
+
+        let constraints = LegConstraints {
+            excluded_first_node: if waypoint_opts.pass_through { previous_node } else { None },
+            departure_heading: heading(i),
+            arrival_heading: if is_last_leg { heading(i + 1) } else { None },
+            heading_tolerance: waypoint_opts.heading_tolerance,
+        };
+
+        let (weight, leg_path) = route_leg(graph, opts, waypoints[i], waypoints[i + 1], &constraints)?;
+
+        legs.push(LegResult {
+            distance: leg_path.get_distance(),
+            time: leg_path.get_time(),
+            weight,
+        });
+        path.add_edges(leg_path.get_edges().to_vec());
+    }
+
+    Some(MultiLegRoutingResult { legs, path })
+}
+
+fn route_leg<G: Graph>(graph: &G, opts: &RoutingAlgorithmOptions<G>, start: usize, end: usize, constraints: &LegConstraints) -> Option<(f64, Path)> {
+    if start == end {
+        return Some((0.0, Path::new(vec![])));
+    }
+
+    let has_departure_constraint = constraints.excluded_first_node.is_some() || constraints.departure_heading.is_some();
+    let has_arrival_constraint = constraints.arrival_heading.is_some();
+
+    //None means that the leg doesn't need a fixed first (or last) edge
+    let first_nodes: Vec<Option<usize>> = if has_departure_constraint {
+        let mut nodes = vec![];
+        graph.do_for_all_neighbors(start, false, |adj_node| {
+            if constraints.excluded_first_node != Some(adj_node)
+                && heading_matches(graph, start, adj_node, constraints.departure_heading, constraints.heading_tolerance)
+            {
+                nodes.push(Some(adj_node));
+            }
+        });
+        nodes
+    } else {
+        vec![None]
+    };
+
+    let last_nodes: Vec<Option<usize>> = if has_arrival_constraint {
+        let mut nodes = vec![];
+        graph.do_for_all_neighbors(end, true, |adj_node| {
+            if heading_matches(graph, adj_node, end, constraints.arrival_heading, constraints.heading_tolerance) {
+                nodes.push(Some(adj_node));
+            }
+        });
+        nodes
+    } else {
+        vec![None]
+    };
+
+    let mut best: Option<(f64, Path)> = None;
+    for first_node in &first_nodes {
+        for last_node in &last_nodes {
+            let candidate = match (first_node, last_node) {
+                //a single edge between start and end satisfies both constraints at once
+                (Some(first_node), Some(last_node)) if *first_node == end && *last_node == start => single_edge_leg(graph, opts, start, end),
+                _ => {
+                    let from = first_node.unwrap_or(start);
+                    let to = last_node.unwrap_or(end);
+                    route_leg_via(graph, opts, start, from, to, end)
+                }
+            };
+
+            if let Some((weight, path)) = candidate {
+                if best.as_ref().is_none_or(|(best_weight, _)| weight < *best_weight) {
+                    best = Some((weight, path));
+                }
+            }
+        }
+    }
+
+    best
+}
+
+//start -> from is an optional fixed first edge, to -> end an optional fixed last edge
+fn route_leg_via<G: Graph>(graph: &G, opts: &RoutingAlgorithmOptions<G>, start: usize, from: usize, to: usize, end: usize) -> Option<(f64, Path)> {
+    let mut weight = 0.0;
+    let mut path = Path::new(vec![]);
+
+    if start != from {
+        let (edge_weight, edge) = create_edge(graph, opts, start, from)?;
+        weight += edge_weight;
+        path.add_edge(edge);
+    }
+
+    if from != to {
+        let mut result = graph.route(opts, from, to)?;
+        weight += result.weight;
+        path.add_edges(result.paths.pop()?.get_edges().to_vec());
+    }
+
+    if to != end {
+        let (edge_weight, edge) = create_edge(graph, opts, to, end)?;
+        weight += edge_weight;
+        path.add_edge(edge);
+    }
+
+    Some((weight, path))
+}
+
+fn single_edge_leg<G: Graph>(graph: &G, opts: &RoutingAlgorithmOptions<G>, start: usize, end: usize) -> Option<(f64, Path)> {
+    let (weight, edge) = create_edge(graph, opts, start, end)?;
+    Some((weight, Path::new(vec![edge])))
+}
+
+fn create_edge<G: Graph>(graph: &G, opts: &RoutingAlgorithmOptions<G>, base_node: usize, adj_node: usize) -> Option<(f64, Rc<EdgeInformation>)> {
+    let weight_calculator = opts.routing_algorithm.get_weight_calculator()?;
+    let edge_info = graph.get_directed_vehicle_specific_edge_information(base_node, adj_node, false, weight_calculator.get_vehicle_type())?;
+    let weight = weight_calculator.calc_weight(base_node, adj_node, &edge_info);
+    if weight == f64::INFINITY {
+        return None;
+    }
+
+    Some((weight, Rc::new(EdgeInformation::new(base_node, adj_node, edge_info))))
+}
+
+fn heading_matches(graph: &impl Graph, base_node: usize, adj_node: usize, heading: Option<f64>, tolerance: f64) -> bool {
+    let heading = match heading {
+        None => return true,
+        Some(h) => h,
+    };
+
+    let (base, adj) = match (graph.get_node(base_node), graph.get_node(adj_node)) {
+        (Some(base), Some(adj)) => (base, adj),
+        _ => return false,
+    };
+
+    let diff = (base.bearing_to(adj) - heading).rem_euclid(360.0);
+    diff.min(360.0 - diff) <= tolerance
+}
@@ -0,0 +1,343 @@
+use super::edge::VehicleTypes;
+use super::geometry::{project_on_segment, Projection};
+use super::Graph;
+
+use std::collections::{HashMap, HashSet};
+use std::io::{self, ErrorKind, Read, Write};
+
+//grid based spatial index over the edges of a graph. Every edge is stored once, independent of its direction
+//an edge is added to every cell that its bounding box overlaps, so queries only have to look at the cells around the query point
+pub struct EdgeIndex {
+    cell_size: f64, //in degrees
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    min_cell: (i64, i64), //bounds of the cells that contain an edge
+    max_cell: (i64, i64),
+    segments: Vec<Segment>,
+}
+
+pub const DEFAULT_CELL_SIZE: f64 = 0.005;
+
+const METERS_PER_DEGREE: f64 = 111_320.0;
+const MAGIC: &[u8; 8] = b"PNAVEIX1";
+
+struct Segment {
+    node1: usize,
+    node2: usize,
+    from: (f64, f64), //lat lon of node1
+    to: (f64, f64),   //lat lon of node2
+}
+
+#[non_exhaustive]
+pub struct EdgeHit {
+    pub node1: usize, //the fraction of the projection goes from node1 to node2
+    pub node2: usize,
+    pub projection: Projection,
+}
+
+impl EdgeIndex {
+    pub fn new(graph: &impl Graph, cell_size: f64) -> Self {
+        let mut index = Self::empty(cell_size);
+        index.rebuild(graph);
+
+        index
+    }
+
+    fn empty(cell_size: f64) -> Self {
+        EdgeIndex {
+            cell_size,
+            cells: HashMap::new(),
+            min_cell: (0, 0),
+            max_cell: (0, 0),
+            segments: vec![],
+        }
+    }
+
+    //only the edges between the nodes, for example the nodes of the components that are kept, so that nothing snaps to the others
+    pub fn with_nodes(graph: &impl Graph, cell_size: f64, nodes: &HashSet<usize>) -> Self {
+        let mut index = Self::empty(cell_size);
+        index.add_edges(graph, |node, adj_node| nodes.contains(&node) && nodes.contains(&adj_node));
+
+        index
+    }
+
+    //only the edges between the nodes that the vehicle type can use, for snapping to the main network of a profile
+    pub fn for_vehicle(graph: &impl Graph, cell_size: f64, vehicle_type: VehicleTypes, nodes: &HashSet<usize>) -> Self {
+        let mut index = Self::empty(cell_size);
+        index.add_edges(graph, |node, adj_node| {
+            let usable = |from: usize, to: usize| {
+                graph
+                    .get_directed_vehicle_specific_edge_information(from, to, false, vehicle_type)
+                    .is_some()
+            };
+            nodes.contains(&node) && nodes.contains(&adj_node) && (usable(node, adj_node) || usable(adj_node, node))
+        });
+
+        index
+    }
+
+    //node indexes change after Graph::keep_nodes, so the index has to be rebuilt afterwards
+    pub fn rebuild(&mut self, graph: &impl Graph) {
+        self.add_edges(graph, |_, _| true);
+    }
+
+    fn add_edges(&mut self, graph: &impl Graph, include: impl Fn(usize, usize) -> bool) {
+        let mut used = HashSet::new();
+        let mut segments = vec![];
+
+        for node in 0..graph.get_nr_nodes() {
+            let mut add_segment = |adj_node: usize| {
+                let key = (node.min(adj_node), node.max(adj_node));
+                if key.0 != key.1 && include(node, adj_node) && used.insert(key) {
+                    if let (Some(from), Some(to)) = (graph.get_node(key.0), graph.get_node(key.1)) {
+                        segments.push(Segment {
+                            node1: key.0,
+                            node2: key.1,
+                            from: (from.lat, from.lon),
+                            to: (to.lat, to.lon),
+                        });
+                    }
+                }
+            };
+
+            graph.do_for_all_neighbors(node, false, &mut add_segment);
+            graph.do_for_all_neighbors(node, true, &mut add_segment);
+        }
+
+        self.set_segments(segments);
+    }
+
+    fn set_segments(&mut self, segments: Vec<Segment>) {
+        self.segments = segments;
+        self.cells = HashMap::new();
+        self.min_cell = (i64::MAX, i64::MAX);
+        self.max_cell = (i64::MIN, i64::MIN);
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            let (min_cell, max_cell) = (
+                self.get_cell(segment.from.0.min(segment.to.0), segment.from.1.min(segment.to.1)),
+                self.get_cell(segment.from.0.max(segment.to.0), segment.from.1.max(segment.to.1)),
+            );
+
+            for x in min_cell.0..=max_cell.0 {
+                for y in min_cell.1..=max_cell.1 {
+                    self.cells.entry((x, y)).or_default().push(i);
+                }
+            }
+
+            self.min_cell = (self.min_cell.0.min(min_cell.0), self.min_cell.1.min(min_cell.1));
+            self.max_cell = (self.max_cell.0.max(max_cell.0), self.max_cell.1.max(max_cell.1));
+        }
+    }
+
+    pub fn get_nr_edges(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// The k closest edges, sorted by distance
+    pub fn nearest(&self, lat: f64, lon: f64, k: usize) -> Vec<EdgeHit> {
+        if k == 0 || self.segments.is_empty() {
+            return vec![];
+        }
+
+        let center = self.get_cell(lat, lon);
+        let max_ring = self.get_max_ring(center);
+
+        //every cell in ring r is at least r - 1 cells away from the query point
+        let min_cell_meters = self.cell_size * METERS_PER_DEGREE * lat.to_radians().cos();
+
+        let mut visited = HashSet::new();
+        let mut hits: Vec<EdgeHit> = vec![];
+        for ring in 0..=max_ring {
+            for cell in ring_cells(center, ring) {
+                self.add_hits(cell, lat, lon, &mut visited, &mut hits, |_| true);
+            }
+
+            if hits.len() >= k {
+                hits.sort_by(|a, b| a.projection.distance.total_cmp(&b.projection.distance));
+                if hits[k - 1].projection.distance <= ring as f64 * min_cell_meters {
+                    break;
+                }
+            }
+        }
+
+        hits.sort_by(|a, b| a.projection.distance.total_cmp(&b.projection.distance));
+        hits.truncate(k);
+        hits
+    }
+
+    /// All edges within radius meters, sorted by distance
+    pub fn within_radius(&self, lat: f64, lon: f64, radius: f64) -> Vec<EdgeHit> {
+        let delta_lat = radius / METERS_PER_DEGREE;
+        let delta_lon = radius / (METERS_PER_DEGREE * lat.to_radians().cos());
+        let min_cell = self.get_cell(lat - delta_lat, lon - delta_lon);
+        let max_cell = self.get_cell(lat + delta_lat, lon + delta_lon);
+
+        let mut visited = HashSet::new();
+        let mut hits = vec![];
+        for x in min_cell.0..=max_cell.0 {
+            for y in min_cell.1..=max_cell.1 {
+                self.add_hits((x, y), lat, lon, &mut visited, &mut hits, |hit| hit.projection.distance <= radius);
+            }
+        }
+
+        hits.sort_by(|a, b| a.projection.distance.total_cmp(&b.projection.distance));
+        hits
+    }
+
+    /// All edges that have a part inside the bounding box, as (node1, node2) pairs
+    pub fn in_bbox(&self, min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> Vec<(usize, usize)> {
+        let min_cell = self.get_cell(min_lat, min_lon);
+        let max_cell = self.get_cell(max_lat, max_lon);
+
+        let mut visited = HashSet::new();
+        let mut ret = vec![];
+        for x in min_cell.0..=max_cell.0 {
+            for y in min_cell.1..=max_cell.1 {
+                for i in self.cells.get(&(x, y)).into_iter().flatten() {
+                    let segment = &self.segments[*i];
+                    if visited.insert(*i) && segment_intersects_bbox(segment.from, segment.to, (min_lat, min_lon), (max_lat, max_lon)) {
+                        ret.push((segment.node1, segment.node2));
+                    }
+                }
+            }
+        }
+
+        ret
+    }
+
+    fn add_hits<F>(&self, cell: (i64, i64), lat: f64, lon: f64, visited: &mut HashSet<usize>, hits: &mut Vec<EdgeHit>, filter: F)
+    where
+        F: Fn(&EdgeHit) -> bool,
+    {
+        for i in self.cells.get(&cell).into_iter().flatten() {
+            if !visited.insert(*i) {
+                continue;
+            }
+
+            let segment = &self.segments[*i];
+            let hit = EdgeHit {
+                node1: segment.node1,
+                node2: segment.node2,
+                projection: project_on_segment(lat, lon, segment.from, segment.to),
+            };
+
+            if filter(&hit) {
+                hits.push(hit);
+            }
+        }
+    }
+
+    fn get_cell(&self, lat: f64, lon: f64) -> (i64, i64) {
+        ((lat / self.cell_size).floor() as i64, (lon / self.cell_size).floor() as i64)
+    }
+
+    //the ring from which all cells of the index have been visited
+    fn get_max_ring(&self, center: (i64, i64)) -> i64 {
+        [
+            center.0 - self.min_cell.0,
+            self.max_cell.0 - center.0,
+            center.1 - self.min_cell.1,
+            self.max_cell.1 - center.1,
+        ]
+        .into_iter()
+        .max()
+        .unwrap()
+        .max(0)
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&self.cell_size.to_le_bytes())?;
+        writer.write_all(&(self.segments.len() as u64).to_le_bytes())?;
+
+        for segment in &self.segments {
+            writer.write_all(&(segment.node1 as u64).to_le_bytes())?;
+            writer.write_all(&(segment.node2 as u64).to_le_bytes())?;
+            for value in [segment.from.0, segment.from.1, segment.to.0, segment.to.1] {
+                writer.write_all(&value.to_le_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    //the cells aren't stored, they are recreated from the edges
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(ErrorKind::InvalidData, "not an edge index file"));
+        }
+
+        let cell_size = read_f64(reader)?;
+        let nr_segments = read_u64(reader)?;
+
+        let mut segments = vec![];
+        for _ in 0..nr_segments {
+            segments.push(Segment {
+                node1: read_u64(reader)? as usize,
+                node2: read_u64(reader)? as usize,
+                from: (read_f64(reader)?, read_f64(reader)?),
+                to: (read_f64(reader)?, read_f64(reader)?),
+            });
+        }
+
+        let mut index = Self::empty(cell_size);
+        index.set_segments(segments);
+
+        Ok(index)
+    }
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_f64(reader: &mut impl Read) -> io::Result<f64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(f64::from_le_bytes(buf))
+}
+
+//all cells at exactly the given chebyshev distance of center
+fn ring_cells(center: (i64, i64), ring: i64) -> Vec<(i64, i64)> {
+    if ring == 0 {
+        return vec![center];
+    }
+
+    let mut ret = vec![];
+    for d in -ring..=ring {
+        ret.push((center.0 + d, center.1 - ring));
+        ret.push((center.0 + d, center.1 + ring));
+    }
+    for d in -ring + 1..ring {
+        ret.push((center.0 - ring, center.1 + d));
+        ret.push((center.0 + ring, center.1 + d));
+    }
+
+    ret
+}
+
+//coordinates are (lat, lon), the segment is treated as a straight line in degrees
+fn segment_intersects_bbox(from: (f64, f64), to: (f64, f64), min: (f64, f64), max: (f64, f64)) -> bool {
+    let inside = |p: (f64, f64)| p.0 >= min.0 && p.0 <= max.0 && p.1 >= min.1 && p.1 <= max.1;
+    if inside(from) || inside(to) {
+        return true;
+    }
+
+    let corners = [(min.0, min.1), (min.0, max.1), (max.0, max.1), (max.0, min.1)];
+    (0..4).any(|i| segments_intersect(from, to, corners[i], corners[(i + 1) % 4]))
+}
+
+pub fn segments_intersect(p1: (f64, f64), p2: (f64, f64), q1: (f64, f64), q2: (f64, f64)) -> bool {
+    let orientation = |a: (f64, f64), b: (f64, f64), c: (f64, f64)| (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+
+    let d1 = orientation(q1, q2, p1);
+    let d2 = orientation(q1, q2, p2);
+    let d3 = orientation(p1, p2, q1);
+    let d4 = orientation(p1, p2, q2);
+
+    ((d1 > 0.0) != (d2 > 0.0)) && ((d3 > 0.0) != (d4 > 0.0))
+}
@@ -1,6 +1,6 @@
 use super::components::options::ComponentsAlgorithmOptions;
-use super::edge::DirectedVehicleSpecificEdgeInformation;
 pub use super::edge::Edge;
+use super::edge::{DirectedVehicleSpecificEdgeInformation, VehicleTypes};
 use super::graph::Graph;
 pub use super::node::Node;
 use super::routing::options::RoutingAlgorithmOptions;
@@ -34,6 +34,12 @@ impl StandardGraph {
             reverse_neighbors: Vec::with_capacity(nr_nodes),
         }
     }
+
+    //the distances of the edges stay the same, so they have to be added again after moving a node
+    pub fn set_location(&mut self, node: usize, lat: f64, lon: f64) {
+        self.nodes[node].lat = lat;
+        self.nodes[node].lon = lon;
+    }
 }
 
 impl Graph for StandardGraph {
@@ -50,8 +56,7 @@ impl Graph for StandardGraph {
     fn add_edge(&mut self, base_node: usize, adj_node: usize, edge: Edge) {
         assert!((base_node) < self.nodes.len() && (adj_node) < self.nodes.len());
 
-        let fwd = edge.is_forward(super::edge::VehicleTypes::Car);
-        let bwd = edge.is_backward(super::edge::VehicleTypes::Car);
+        let (fwd, bwd) = edge.is_accessible_by_any_vehicle();
 
         let reverse_edge = Rc::new(edge.create_opposite());
         let rc_edge = Rc::new(edge);
@@ -67,6 +72,13 @@ impl Graph for StandardGraph {
         }
     }
 
+    fn remove_edge(&mut self, base_node: usize, adj_node: usize) {
+        self.neighbors[base_node].remove(&adj_node);
+        self.reverse_neighbors[adj_node].remove(&base_node);
+        self.neighbors[adj_node].remove(&base_node);
+        self.reverse_neighbors[base_node].remove(&adj_node);
+    }
+
     fn keep_nodes(&mut self, nodes: &HashSet<usize>) {
         let mut index = 0;
         let mut remaining_index = 0_usize;
@@ -157,12 +169,13 @@ impl Graph for StandardGraph {
         base_node: usize,
         adj_node: usize,
         reverse: bool,
+        vehicle_type: VehicleTypes,
     ) -> Option<Rc<DirectedVehicleSpecificEdgeInformation>> {
         let relevant_neighbors = if reverse { &self.reverse_neighbors } else { &self.neighbors };
 
         relevant_neighbors.get(base_node).and_then(|n| {
             n.get(&adj_node)
-                .and_then(|e| e.get_directed_vehicle_specific_edge_information(super::edge::VehicleTypes::Car, reverse))
+                .and_then(|e| e.get_directed_vehicle_specific_edge_information(vehicle_type, reverse))
         })
     }
 
@@ -324,13 +337,13 @@ mod tests {
         graph.do_for_all_neighbors(0, true, |adj_node| {
             adj_nodes.insert(adj_node);
         });
-        assert!(adj_nodes.len() == 0, "adj nodes: {:?}", adj_nodes);
+        assert!(adj_nodes.is_empty(), "adj nodes: {:?}", adj_nodes);
 
         let mut adj_nodes = HashSet::new();
         graph.do_for_all_neighbors(1, false, |adj_node| {
             adj_nodes.insert(adj_node);
         });
-        assert!(adj_nodes.len() == 0, "adj nodes: {:?}", adj_nodes);
+        assert!(adj_nodes.is_empty(), "adj nodes: {:?}", adj_nodes);
 
         let mut adj_nodes = HashSet::new();
         graph.do_for_all_neighbors(1, true, |adj_node| {
@@ -349,13 +362,13 @@ mod tests {
         graph.do_for_all_neighbors(3, true, |adj_node| {
             adj_nodes.insert(adj_node);
         });
-        assert!(adj_nodes.len() == 0, "adj nodes: {:?}", adj_nodes);
+        assert!(adj_nodes.is_empty(), "adj nodes: {:?}", adj_nodes);
 
         let mut adj_nodes = HashSet::new();
         graph.do_for_all_neighbors(2, false, |adj_node| {
             adj_nodes.insert(adj_node);
         });
-        assert!(adj_nodes.len() == 0, "adj nodes: {:?}", adj_nodes);
+        assert!(adj_nodes.is_empty(), "adj nodes: {:?}", adj_nodes);
 
         let mut adj_nodes = HashSet::new();
         graph.do_for_all_neighbors(2, true, |adj_node| {
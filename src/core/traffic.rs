@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::io::{self, ErrorKind};
+use std::rc::Rc;
+
+use super::Graph;
+
+//live speeds that replace the speeds of the graph, without changing the graph itself
+//an update applies to both directions of the edges
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrafficKey {
+    Way(i64),       //every edge created from this osm way
+    Edge(i64, i64), //the edge between these osm nodes, in either order. Both have to be tower nodes, the ends of an edge of the graph
+}
+
+//finds the graph edges of TrafficKey::Edge, graph indices change when the graph is filtered but osm ids don't
+pub struct EdgeLookup<'g, G: Graph> {
+    graph: &'g G,
+    indices: HashMap<i64, Vec<usize>>, //a barrier in the middle of a way has a copy with the same osm id
+}
+
+impl<'g, G: Graph> EdgeLookup<'g, G> {
+    pub fn new(graph: &'g G) -> Self {
+        let mut indices: HashMap<i64, Vec<usize>> = HashMap::new();
+        for node in 0..graph.get_nr_nodes() {
+            indices.entry(graph.get_node(node).unwrap().osm_id).or_default().push(node);
+        }
+        EdgeLookup { graph, indices }
+    }
+
+    //the (smallest index, largest index) of the graph edges between the osm nodes, an error if there are none
+    pub fn find(&self, osm_id1: i64, osm_id2: i64) -> Result<Vec<(usize, usize)>, io::Error> {
+        let indices = |osm_id| {
+            self.indices
+                .get(&osm_id)
+                .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, format!("node {} is not in the graph", osm_id)))
+        };
+        let (nodes1, nodes2) = (indices(osm_id1)?, indices(osm_id2)?);
+
+        let mut edges = vec![];
+        for node1 in nodes1 {
+            for reverse in [false, true] {
+                self.graph.do_for_all_neighbors(*node1, reverse, |adj_node| {
+                    if nodes2.contains(&adj_node) {
+                        edges.push(((*node1).min(adj_node), (*node1).max(adj_node)));
+                    }
+                });
+            }
+        }
+        edges.sort_unstable();
+        edges.dedup();
+
+        if edges.is_empty() {
+            return Err(io::Error::new(ErrorKind::InvalidData, format!("there is no edge between node {} and node {}", osm_id1, osm_id2)));
+        }
+        Ok(edges)
+    }
+}
+
+//(smallest osm id, largest osm id)
+fn osm_edge(osm_id1: i64, osm_id2: i64) -> (i64, i64) {
+    (osm_id1.min(osm_id2), osm_id1.max(osm_id2))
+}
+
+//(smallest index, largest index)
+fn graph_edge(node1: usize, node2: usize) -> (usize, usize) {
+    (node1.min(node2), node1.max(node2))
+}
+
+#[derive(Clone, Debug)]
+pub struct SpeedUpdate {
+    pub key: TrafficKey,
+    pub speed: f64,              //in km/h
+    pub expires_at: Option<f64>, //seconds since the unix epoch, None if the update doesn't expire
+}
+
+impl SpeedUpdate {
+    pub fn is_expired(&self, time: f64) -> bool {
+        self.expires_at.is_some_and(|e| e <= time)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct TrafficOverlay {
+    way_updates: HashMap<i64, SpeedUpdate>,
+    edge_updates: HashMap<(i64, i64), SpeedUpdate>, //(smallest osm id, largest osm id)
+    edges: HashMap<(usize, usize), (i64, i64)>,     //the osm edge of every graph edge with an update
+}
+
+impl TrafficOverlay {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    //replaces an earlier update with the same key, an error if the nodes of an edge update aren't an edge of the graph
+    pub fn apply<G: Graph>(&mut self, update: SpeedUpdate, lookup: &EdgeLookup<G>) -> Result<(), io::Error> {
+        match update.key {
+            TrafficKey::Way(way_id) => {
+                self.way_updates.insert(way_id, update);
+            }
+            TrafficKey::Edge(osm_id1, osm_id2) => {
+                let osm_edge = osm_edge(osm_id1, osm_id2);
+                self.edges.extend(lookup.find(osm_id1, osm_id2)?.into_iter().map(|edge| (edge, osm_edge)));
+                self.edge_updates.insert(osm_edge, update);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_updates(&self) -> Vec<&SpeedUpdate> {
+        self.way_updates.values().chain(self.edge_updates.values()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.way_updates.is_empty() && self.edge_updates.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.way_updates.clear();
+        self.edge_updates.clear();
+        self.edges.clear();
+    }
+
+    pub fn remove_expired(&mut self, time: f64) {
+        self.way_updates.retain(|_, u| !u.is_expired(time));
+        self.edge_updates.retain(|_, u| !u.is_expired(time));
+        self.edges.retain(|_, osm_edge| self.edge_updates.contains_key(osm_edge));
+    }
+
+    /// The speed in m/s at the given time, if there is an update for this edge
+    /// an update for the edge itself has priority over an update for its way
+    pub fn get_speed(&self, way_id: Option<i64>, base_node: usize, adj_node: usize, time: f64) -> Option<f64> {
+        let edge_update = self.edges.get(&graph_edge(base_node, adj_node)).and_then(|e| self.edge_updates.get(e));
+        let way_update = way_id.and_then(|id| self.way_updates.get(&id));
+
+        [edge_update, way_update]
+            .into_iter()
+            .flatten()
+            .find(|u| !u.is_expired(time))
+            .map(|u| u.speed / 3.6)
+    }
+}
+
+//historical speeds per 15 minute slot of the week, used by time dependent routing
+
+pub const SLOT_SECONDS: f64 = 900.0;
+const WEEK_SECONDS: f64 = 7.0 * 86400.0;
+const MONDAY_OFFSET: f64 = 3.0 * 86400.0; //the unix epoch is on a thursday
+
+#[derive(Clone, Debug)]
+pub struct SpeedProfile {
+    speeds: Vec<f64>, //in km/h, the first slot starts on monday 00:00 utc
+}
+
+impl SpeedProfile {
+    //96 speeds are used for every day of the week, 672 speeds are one week
+    pub fn new(speeds: Vec<f64>) -> Option<Self> {
+        if (speeds.len() == 96 || speeds.len() == 672) && speeds.iter().all(|s| *s >= 0.0) {
+            Some(SpeedProfile { speeds })
+        } else {
+            None
+        }
+    }
+
+    //speed in m/s at the given time (seconds since the unix epoch)
+    pub fn get_speed(&self, time: f64) -> f64 {
+        let time_of_week = (time + MONDAY_OFFSET).rem_euclid(WEEK_SECONDS);
+        let slot = (time_of_week / SLOT_SECONDS) as usize % self.speeds.len();
+        self.speeds[slot] / 3.6
+    }
+
+    /// Seconds needed to drive the distance when leaving at departure
+    /// the speed changes at every slot boundary instead of using the speed at departure for the whole distance,
+    /// so leaving later never means arriving earlier (FIFO). A speed of 0 means waiting for the next slot
+    pub fn travel_time(&self, distance: f64, departure: f64) -> f64 {
+        //the distance driven in a whole period of the profile, from any time
+        let period_distance: f64 = self.speeds.iter().map(|s| s / 3.6 * SLOT_SECONDS).sum();
+        if period_distance <= 0.0 {
+            return f64::INFINITY;
+        }
+
+        //skip the whole periods, the last one is driven slot by slot
+        let periods = ((distance / period_distance).ceil() - 1.0).max(0.0);
+        let mut remaining = distance - periods * period_distance;
+        let mut elapsed = periods * self.speeds.len() as f64 * SLOT_SECONDS;
+
+        loop {
+            let time = departure + elapsed;
+            let speed = self.get_speed(time);
+            let slot_left = ((time / SLOT_SECONDS).floor() + 1.0) * SLOT_SECONDS - time;
+
+            if speed > 0.0 && remaining <= speed * slot_left {
+                return elapsed + remaining / speed;
+            }
+
+            remaining -= speed * slot_left;
+            elapsed += slot_left;
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SpeedProfiles {
+    way_profiles: HashMap<i64, Rc<SpeedProfile>>,
+    edge_profiles: HashMap<(usize, usize), Rc<SpeedProfile>>, //(smallest index, largest index) of the graph edges
+}
+
+impl SpeedProfiles {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    //many ways usually share the same profile, so it is stored behind an Rc
+    //an error if the nodes of an edge profile aren't an edge of the graph
+    pub fn add<G: Graph>(&mut self, key: TrafficKey, profile: Rc<SpeedProfile>, lookup: &EdgeLookup<G>) -> Result<(), io::Error> {
+        match key {
+            TrafficKey::Way(way_id) => {
+                self.way_profiles.insert(way_id, profile);
+            }
+            TrafficKey::Edge(osm_id1, osm_id2) => {
+                for edge in lookup.find(osm_id1, osm_id2)? {
+                    self.edge_profiles.insert(edge, Rc::clone(&profile));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.way_profiles.is_empty() && self.edge_profiles.is_empty()
+    }
+
+    //a profile for the edge itself has priority over a profile for its way
+    pub fn get_profile(&self, way_id: Option<i64>, base_node: usize, adj_node: usize) -> Option<&SpeedProfile> {
+        self.edge_profiles
+            .get(&graph_edge(base_node, adj_node))
+            .or_else(|| way_id.and_then(|id| self.way_profiles.get(&id)))
+            .map(|p| p.as_ref())
+    }
+}
@@ -1,25 +1,64 @@
+use std::collections::HashSet;
 use std::rc::Rc;
 
-use super::edge::DirectedVehicleSpecificEdgeInformation;
 pub use super::edge::Edge;
+use super::edge::{DirectedVehicleSpecificEdgeInformation, PhysicalRestrictions, RoadClass, VehicleTypes};
+use super::traffic::TrafficOverlay;
 
 #[non_exhaustive]
 pub enum WeightCalculator {
     Distance(DistanceWeight),
     TravelTime(TravelTimeWeight),
+    Custom(CustomWeight),
+    AvoidAreas(AvoidAreasWeight),
+    Conditional(ConditionalWeight),
+    Dimensions(DimensionsWeight),
 }
 
 impl WeightCalculator {
+    //base_node and adj_node are the nodes of the edge, in either order
     #[inline(always)]
-    pub fn calc_weight(&self, edge: &Rc<DirectedVehicleSpecificEdgeInformation>) -> f64 {
+    pub fn calc_weight(&self, base_node: usize, adj_node: usize, edge: &Rc<DirectedVehicleSpecificEdgeInformation>) -> f64 {
+        self.calc_weight_with_access(base_node, adj_node, edge, edge.is_accessible())
+    }
+
+    /// Same as calc_weight, but accessible replaces the access of the edge
+    /// used for edges with conditional restrictions, whose access depends on the time
+    #[inline(always)]
+    pub fn calc_weight_with_access(
+        &self,
+        base_node: usize,
+        adj_node: usize,
+        edge: &Rc<DirectedVehicleSpecificEdgeInformation>,
+        accessible: bool,
+    ) -> f64 {
         match self {
+            WeightCalculator::AvoidAreas(v) => v.calc_weight(base_node, adj_node, edge, accessible),
+            WeightCalculator::Conditional(v) => v.calc_weight(base_node, adj_node, edge),
+            WeightCalculator::Dimensions(v) => v.calc_weight(base_node, adj_node, edge, accessible),
+            _ if !accessible => f64::INFINITY,
             WeightCalculator::Distance(v) => v.calc_weight(edge),
-            WeightCalculator::TravelTime(v) => v.calc_weight(edge),
+            WeightCalculator::TravelTime(v) => v.calc_weight(base_node, adj_node, edge),
+            WeightCalculator::Custom(v) => v.calc_weight(edge),
+        }
+    }
+
+    //the vehicle type whose edge information is used
+    pub fn get_vehicle_type(&self) -> VehicleTypes {
+        match self {
+            WeightCalculator::Distance(v) => v.vehicle_type,
+            WeightCalculator::TravelTime(v) => v.vehicle_type,
+            WeightCalculator::Custom(v) => v.vehicle_type,
+            WeightCalculator::AvoidAreas(v) => v.weight_calculator.get_vehicle_type(),
+            WeightCalculator::Conditional(v) => v.weight_calculator.get_vehicle_type(),
+            WeightCalculator::Dimensions(v) => v.weight_calculator.get_vehicle_type(),
         }
     }
 }
 
-pub struct DistanceWeight {}
+pub struct DistanceWeight {
+    pub vehicle_type: VehicleTypes,
+}
 
 impl DistanceWeight {
     #[inline(always)]
@@ -28,15 +67,150 @@ impl DistanceWeight {
     }
 }
 
-pub struct TravelTimeWeight {}
+pub struct TravelTimeWeight {
+    pub vehicle_type: VehicleTypes,
+    pub traffic: Option<Traffic>,
+}
+
+//the traffic overlay is shared, so updates don't have to copy it while no request is using it
+pub struct Traffic {
+    pub overlay: Rc<TrafficOverlay>,
+    pub time: f64, //seconds since the unix epoch, updates that are expired at this time are ignored
+}
 
 impl TravelTimeWeight {
     #[inline(always)]
-    fn calc_weight(&self, edge: &Rc<DirectedVehicleSpecificEdgeInformation>) -> f64 {
-        let speed = edge.get_speed();
+    fn calc_weight(&self, base_node: usize, adj_node: usize, edge: &Rc<DirectedVehicleSpecificEdgeInformation>) -> f64 {
+        let speed = self
+            .traffic
+            .as_ref()
+            .and_then(|t| t.overlay.get_speed(edge.get_attributes().way_id, base_node, adj_node, t.time))
+            .unwrap_or_else(|| edge.get_speed());
         if speed > 0.0 {
-            return edge.get_distance() / speed;
+            return edge.get_distance() / speed + edge.get_time_penalty();
         }
         f64::INFINITY
     }
 }
+
+//rules that are evaluated per request, so every request can have its own preferences on the same graph
+//the factors are priorities: the weight of a matching edge is divided by the factor, so 0.5 makes an edge twice as expensive and 0 avoids it
+#[derive(Clone, Debug)]
+pub enum CustomRule {
+    RoadClass(RoadClass, f64),
+    Toll(f64),
+    Ferry(f64),
+    MaxSpeed(f64), //in km/h, only changes travel time based weights
+}
+
+pub struct CustomWeight {
+    pub vehicle_type: VehicleTypes,
+    pub travel_time: bool, //the weight before applying the rules is the travel time instead of the distance
+    pub rules: Vec<CustomRule>,
+}
+
+impl CustomWeight {
+    #[inline(always)]
+    fn calc_weight(&self, edge: &Rc<DirectedVehicleSpecificEdgeInformation>) -> f64 {
+        let attributes = edge.get_attributes();
+        let mut priority = 1.0;
+        let mut speed = edge.get_speed();
+
+        for rule in self.rules.iter() {
+            match rule {
+                CustomRule::RoadClass(road_class, factor) if attributes.road_class == *road_class => priority *= factor,
+                CustomRule::Toll(factor) if attributes.toll => priority *= factor,
+                CustomRule::Ferry(factor) if attributes.ferry => priority *= factor,
+                CustomRule::MaxSpeed(max_speed) => speed = speed.min(max_speed / 3.6),
+                _ => {}
+            }
+        }
+
+        if priority <= 0.0 {
+            return f64::INFINITY;
+        }
+
+        let weight = if !self.travel_time {
+            edge.get_distance()
+        } else if speed > 0.0 {
+            edge.get_distance() / speed + edge.get_time_penalty()
+        } else {
+            f64::INFINITY
+        };
+
+        weight / priority
+    }
+}
+
+//wraps another weight calculator and blocks the given edges. The blocked edges are looked up once per request, see avoidareas
+pub struct AvoidAreasWeight {
+    pub weight_calculator: Box<WeightCalculator>,
+    pub blocked_edges: HashSet<(usize, usize)>, //(smallest node, largest node)
+}
+
+impl AvoidAreasWeight {
+    #[inline(always)]
+    fn calc_weight(&self, base_node: usize, adj_node: usize, edge: &Rc<DirectedVehicleSpecificEdgeInformation>, accessible: bool) -> f64 {
+        if self.blocked_edges.contains(&(base_node.min(adj_node), base_node.max(adj_node))) {
+            return f64::INFINITY;
+        }
+
+        self.weight_calculator.calc_weight_with_access(base_node, adj_node, edge, accessible)
+    }
+}
+
+//wraps another weight calculator and applies the conditional restrictions of the edges at the given time
+pub struct ConditionalWeight {
+    pub weight_calculator: Box<WeightCalculator>,
+    pub local_time: f64, //seconds since the unix epoch plus the utc offset of the area
+}
+
+impl ConditionalWeight {
+    #[inline(always)]
+    fn calc_weight(&self, base_node: usize, adj_node: usize, edge: &Rc<DirectedVehicleSpecificEdgeInformation>) -> f64 {
+        self.weight_calculator
+            .calc_weight_with_access(base_node, adj_node, edge, edge.is_accessible_at(self.local_time))
+    }
+}
+
+//the vehicle of a request, weights are in tonnes and sizes in metres
+#[derive(Clone, Debug, Default)]
+pub struct VehicleDimensions {
+    pub weight: f64,
+    pub axle_load: f64,
+    pub height: f64,
+    pub width: f64,
+    pub length: f64,
+    pub hazmat: bool, //the vehicle carries hazardous goods
+}
+
+impl VehicleDimensions {
+    //a limit equal to the dimension is still allowed
+    pub fn fits(&self, restrictions: &PhysicalRestrictions) -> bool {
+        let within = |value: f64, limit: Option<f64>| limit.is_none_or(|l| value <= l);
+
+        within(self.weight, restrictions.max_weight)
+            && within(self.axle_load, restrictions.max_axle_load)
+            && within(self.height, restrictions.max_height)
+            && within(self.width, restrictions.max_width)
+            && within(self.length, restrictions.max_length)
+            && !(self.hazmat && restrictions.no_hazmat)
+    }
+}
+
+//wraps another weight calculator and excludes the edges whose limits the vehicle exceeds
+pub struct DimensionsWeight {
+    pub weight_calculator: Box<WeightCalculator>,
+    pub dimensions: VehicleDimensions,
+}
+
+impl DimensionsWeight {
+    #[inline(always)]
+    fn calc_weight(&self, base_node: usize, adj_node: usize, edge: &Rc<DirectedVehicleSpecificEdgeInformation>, accessible: bool) -> f64 {
+        if !self.dimensions.fits(&edge.get_attributes().restrictions) {
+            return f64::INFINITY;
+        }
+
+        self.weight_calculator.calc_weight_with_access(base_node, adj_node, edge, accessible)
+    }
+}
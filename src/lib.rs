@@ -1,2 +1,4 @@
 pub mod core;
 pub mod reader;
+pub mod router;
+pub mod writer;
@@ -1,6 +1,6 @@
 use std::time::Instant;
 
-mod router;
+use perionav::router;
 
 fn main() {
     let router = router::new_router("./data/germany-latest.osm.pbf");
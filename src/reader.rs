@@ -1,3 +1,11 @@
+pub mod boundary;
+pub mod edge_list_reader;
+pub mod gps_reader;
+pub mod node_bitset;
 pub mod osm_reader;
+pub mod osm_updater;
+pub mod osm_xml_reader;
+pub mod profile;
 pub mod tags_map;
+pub mod traffic_reader;
 pub mod vehicle_permissions;
@@ -0,0 +1,112 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+
+use crate::core::avoidareas::Area;
+
+/// The part of the osm file that is imported: nodes inside one of the areas and not inside one of the holes
+#[derive(Clone, Debug, Default)]
+pub struct Boundary {
+    pub areas: Vec<Area>,
+    pub holes: Vec<Area>,
+}
+
+impl Boundary {
+    pub fn from_bbox(min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> Self {
+        Boundary {
+            areas: vec![Area::BBox {
+                min_lat,
+                min_lon,
+                max_lat,
+                max_lon,
+            }],
+            holes: vec![],
+        }
+    }
+
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        self.areas.iter().any(|a| a.contains(lat, lon)) && !self.holes.iter().any(|h| h.contains(lat, lon))
+    }
+}
+
+//reads a .poly file, the format of osmosis and osmium (https://wiki.openstreetmap.org/wiki/Osmosis/Polygon_Filter_File_Format)
+pub fn read_poly(file_name: &str) -> Result<Boundary, io::Error> {
+    parse_poly(&fs::read_to_string(file_name)?)
+}
+
+//a name line, then rings of "lon lat" lines that each end with END, rings with a name starting with ! are holes. The file ends with END
+pub fn parse_poly(text: &str) -> Result<Boundary, io::Error> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(nr, line)| (nr + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+    lines.next(); //the name of the polygon
+
+    let mut boundary = Boundary::default();
+    loop {
+        let (_, ring_name) = lines
+            .next()
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "poly file doesn't end with END"))?;
+        if ring_name == "END" {
+            break;
+        }
+
+        let mut ring = vec![];
+        loop {
+            let (line_nr, line) = lines
+                .next()
+                .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, format!("ring {} doesn't end with END", ring_name)))?;
+            if line == "END" {
+                break;
+            }
+
+            let coordinates: Vec<f64> = line.split_whitespace().filter_map(|c| c.parse::<f64>().ok()).collect();
+            if coordinates.len() != 2 {
+                return Err(io::Error::new(ErrorKind::InvalidData, format!("invalid coordinate on line {}", line_nr)));
+            }
+            ring.push((coordinates[1], coordinates[0]));
+        }
+
+        if ring_name.starts_with('!') {
+            boundary.holes.push(Area::Polygon(ring));
+        } else {
+            boundary.areas.push(Area::Polygon(ring));
+        }
+    }
+
+    Ok(boundary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_poly() {
+        let text = "ghent
+1
+   3.6   51.0
+   3.8   51.0
+   3.8E+00   5.12E+01
+   3.6   51.2
+END
+!2
+   3.69   51.09
+   3.71   51.09
+   3.71   51.11
+   3.69   51.11
+END
+END
+";
+        let boundary = parse_poly(text).unwrap();
+        assert_eq!(boundary.areas.len(), 1);
+        assert_eq!(boundary.holes.len(), 1);
+
+        assert!(boundary.contains(51.05, 3.7));
+        assert!(!boundary.contains(51.1, 3.7));
+        assert!(!boundary.contains(51.05, 3.9));
+
+        assert!(parse_poly("ghent\n1\n3.6 51.0\nEND\n").is_err());
+        assert!(parse_poly("ghent\n1\n3.6\nEND\nEND\n").is_err());
+    }
+}
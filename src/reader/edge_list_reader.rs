@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, ErrorKind};
+
+use serde_json::Value;
+
+use crate::core::edge::{EdgeAttributes, RoadClass, VehicleAccess, VehicleTypes};
+use crate::core::geometry::haversine_distance;
+use crate::core::{Edge, Graph, Node, StandardGraph};
+
+//graphs from formats without osm tags. The edges are for cars, distances are in meters and speeds in km/h
+
+const DEFAULT_SPEED: f64 = 50.0; //for geojson features without a speed
+
+//nodes are created the first time their id is used, their location can be set later
+#[derive(Default)]
+struct GraphBuilder {
+    indices: HashMap<i64, usize>,
+    nodes: Vec<Node>,
+    edges: Vec<(usize, usize, Edge)>,
+}
+
+impl GraphBuilder {
+    fn get_index(&mut self, id: i64) -> usize {
+        *self.indices.entry(id).or_insert_with(|| {
+            self.nodes.push(Node::new(id, 0.0, 0.0));
+            self.nodes.len() - 1
+        })
+    }
+
+    fn set_location(&mut self, index: usize, (lat, lon): (f64, f64)) {
+        self.nodes[index].lat = lat;
+        self.nodes[index].lon = lon;
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, edge: Edge) {
+        self.edges.push((from, to, edge));
+    }
+
+    fn build(self) -> StandardGraph {
+        let mut g = StandardGraph::new(self.nodes.len());
+        for node in self.nodes {
+            g.add_node(node);
+        }
+        for (from, to, edge) in self.edges {
+            g.add_edge(from, to, edge);
+        }
+
+        g
+    }
+}
+
+fn car_edge(distance: f64, attributes: EdgeAttributes, forward: bool, backward: bool, speed: f64) -> Edge {
+    let access = VehicleAccess {
+        forward,
+        backward,
+        forward_speed: speed / 3.6,
+        backward_speed: speed / 3.6,
+        conditions: vec![],
+    };
+
+    Edge::with_attributes(distance, attributes, vec![(VehicleTypes::Car, access)])
+}
+
+/// Reads a csv edge list with lines: from_id,to_id,distance,speed,fwd,bwd,geometry
+/// fwd and bwd are 1/0, true/false or yes/no. The geometry is optional: a wkt LINESTRING (lon lat, ...), quoted because it contains commas.
+/// it gives the locations of the nodes and the distance if the distance field is empty. A header line is allowed
+pub fn read_csv_graph(file_name: &str) -> Result<impl Graph, io::Error> {
+    let reader = BufReader::new(File::open(file_name)?);
+
+    let mut builder = GraphBuilder::default();
+    for (line_nr, line) in reader.lines().enumerate() {
+        let line = line?;
+        let fields: Vec<&str> = line.splitn(7, ',').map(|f| f.trim()).collect();
+        if line.trim().is_empty() || (line_nr == 0 && fields[0].parse::<i64>().is_err()) {
+            continue;
+        }
+
+        let invalid = |message: &str| io::Error::new(ErrorKind::InvalidData, format!("{} on line {}", message, line_nr + 1));
+        if fields.len() < 6 {
+            return Err(invalid("expected at least 6 fields"));
+        }
+
+        let parse = |field: &str| field.parse::<f64>().map_err(|_| invalid(&format!("invalid number {}", field)));
+        let parse_id = |field: &str| field.parse::<i64>().map_err(|_| invalid(&format!("invalid id {}", field)));
+        let parse_bool = |field: &str| match field {
+            "1" | "true" | "yes" => Ok(true),
+            "0" | "false" | "no" => Ok(false),
+            _ => Err(invalid(&format!("invalid direction {}", field))),
+        };
+
+        let geometry = match fields.get(6).map(|g| g.trim_matches('"')) {
+            Some(wkt) if !wkt.is_empty() => Some(parse_wkt_line_string(wkt).ok_or_else(|| invalid("invalid geometry"))?),
+            _ => None,
+        };
+        let distance = match (fields[2], &geometry) {
+            ("", Some(points)) => line_length(points),
+            ("", None) => return Err(invalid("no distance and no geometry")),
+            (distance, _) => parse(distance)?,
+        };
+
+        let from = builder.get_index(parse_id(fields[0])?);
+        let to = builder.get_index(parse_id(fields[1])?);
+        if let Some(points) = geometry {
+            builder.set_location(from, points[0]);
+            builder.set_location(to, points[points.len() - 1]);
+        }
+
+        let edge = car_edge(distance, EdgeAttributes::default(), parse_bool(fields[4])?, parse_bool(fields[5])?, parse(fields[3])?);
+        builder.add_edge(from, to, edge);
+    }
+
+    Ok(builder.build())
+}
+
+//LINESTRING (3.7 51.0, 3.71 51.0) to (lat, lon) points
+fn parse_wkt_line_string(wkt: &str) -> Option<Vec<(f64, f64)>> {
+    let coordinates = wkt.strip_prefix("LINESTRING")?.trim().strip_prefix('(')?.strip_suffix(')')?;
+
+    let points = coordinates
+        .split(',')
+        .map(|point| {
+            let mut parts = point.split_whitespace().map(|c| c.parse::<f64>().ok());
+            let (lon, lat) = (parts.next()??, parts.next()??);
+            Some((lat, lon))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    (points.len() >= 2).then_some(points)
+}
+
+//points are (lat, lon)
+fn line_length(points: &[(f64, f64)]) -> f64 {
+    points.windows(2).map(|w| haversine_distance(w[0].0, w[0].1, w[1].0, w[1].1)).sum()
+}
+
+/// Reads a graph of the 9th DIMACS implementation challenge: arcs "a <from> <to> <weight>" from the .gr file
+/// and optionally the locations "v <id> <lon * 10^6> <lat * 10^6>" from the .co file
+/// arcs are directed and the weight becomes the distance with a speed of 1, so routes have the weights of published results
+pub fn read_dimacs_graph(gr_file_name: &str, co_file_name: Option<&str>) -> Result<impl Graph, io::Error> {
+    let mut builder = GraphBuilder::default();
+
+    read_dimacs_lines(gr_file_name, |fields, invalid| {
+        match fields {
+            ["p", "sp", nr_nodes, _] => {
+                //create the nodes in the order of their id
+                let nr_nodes = nr_nodes.parse::<i64>().map_err(|_| invalid("invalid number of nodes"))?;
+                (1..=nr_nodes).for_each(|id| {
+                    builder.get_index(id);
+                });
+            }
+            ["a", from, to, weight] => {
+                let from = builder.get_index(from.parse::<i64>().map_err(|_| invalid("invalid node id"))?);
+                let to = builder.get_index(to.parse::<i64>().map_err(|_| invalid("invalid node id"))?);
+                let weight = weight.parse::<f64>().map_err(|_| invalid("invalid weight"))?;
+                builder.add_edge(from, to, car_edge(weight, EdgeAttributes::default(), true, false, 3.6));
+            }
+            _ => return Err(invalid("expected a p or an a line")),
+        }
+        Ok(())
+    })?;
+
+    if let Some(co_file_name) = co_file_name {
+        read_dimacs_lines(co_file_name, |fields, invalid| {
+            match fields {
+                ["p", ..] => {}
+                ["v", id, lon, lat] => {
+                    let id = id.parse::<i64>().map_err(|_| invalid("invalid node id"))?;
+                    let index = *builder.indices.get(&id).ok_or_else(|| invalid("unknown node id"))?;
+                    let coordinate = |c: &str| c.parse::<f64>().map(|c| c / 1E6).map_err(|_| invalid("invalid coordinate"));
+                    builder.set_location(index, (coordinate(lat)?, coordinate(lon)?));
+                }
+                _ => return Err(invalid("expected a p or a v line")),
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(builder.build())
+}
+
+//calls f with the fields of every line that isn't empty or a comment
+fn read_dimacs_lines<F>(file_name: &str, mut f: F) -> Result<(), io::Error>
+where
+    F: FnMut(&[&str], &dyn Fn(&str) -> io::Error) -> Result<(), io::Error>,
+{
+    let reader = BufReader::new(File::open(file_name)?);
+    for (line_nr, line) in reader.lines().enumerate() {
+        let line = line?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.is_empty() || fields[0] == "c" {
+            continue;
+        }
+
+        let invalid = |message: &str| io::Error::new(ErrorKind::InvalidData, format!("{} on line {}", message, line_nr + 1));
+        f(&fields, &invalid)?;
+    }
+
+    Ok(())
+}
+
+/// Reads a geojson FeatureCollection of LineString and MultiLineString features, other geometries are skipped
+/// lines that share an end point are connected there. Properties that are used:
+/// speed (km/h, 50 by default), oneway (true, "yes" or "-1" for the opposite direction) and highway for the road class
+pub fn read_geojson_graph(file_name: &str) -> Result<impl Graph, io::Error> {
+    let json: Value = serde_json::from_str(&fs::read_to_string(file_name)?).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+    let features = json["features"]
+        .as_array()
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "geojson without features"))?;
+
+    let mut builder = GraphBuilder::default();
+    let mut node_ids: HashMap<(i64, i64), i64> = HashMap::new(); //end points by their coordinates in 10^-7 degrees
+
+    for (feature_nr, feature) in features.iter().enumerate() {
+        let invalid = |message: &str| io::Error::new(ErrorKind::InvalidData, format!("{} in feature {}", message, feature_nr));
+
+        let geometry = &feature["geometry"];
+        let lines = match geometry["type"].as_str() {
+            Some("LineString") => vec![&geometry["coordinates"]],
+            Some("MultiLineString") => geometry["coordinates"].as_array().map(|l| l.iter().collect()).unwrap_or_default(),
+            _ => continue,
+        };
+
+        let properties = &feature["properties"];
+        let speed = properties["speed"].as_f64().unwrap_or(DEFAULT_SPEED);
+        let (forward, backward) = match &properties["oneway"] {
+            Value::Bool(true) => (true, false),
+            Value::String(oneway) if oneway == "yes" => (true, false),
+            Value::String(oneway) if oneway == "-1" => (false, true),
+            _ => (true, true),
+        };
+        let attributes = EdgeAttributes {
+            road_class: properties["highway"].as_str().map_or(RoadClass::Other, RoadClass::from_highway),
+            ..Default::default()
+        };
+
+        for line in lines {
+            let points = parse_geojson_line(line).ok_or_else(|| invalid("invalid LineString coordinates"))?;
+
+            let mut ends = [0, 0];
+            for (end, point) in ends.iter_mut().zip([points[0], points[points.len() - 1]]) {
+                let key = ((point.0 * 1E7).round() as i64, (point.1 * 1E7).round() as i64);
+                let next_id = node_ids.len() as i64;
+                *end = builder.get_index(*node_ids.entry(key).or_insert(next_id));
+                builder.set_location(*end, point);
+            }
+
+            builder.add_edge(ends[0], ends[1], car_edge(line_length(&points), attributes.clone(), forward, backward, speed));
+        }
+    }
+
+    Ok(builder.build())
+}
+
+//[[lon, lat], ...] to (lat, lon) points
+fn parse_geojson_line(coordinates: &Value) -> Option<Vec<(f64, f64)>> {
+    let points = coordinates
+        .as_array()?
+        .iter()
+        .map(|point| Some((point.get(1)?.as_f64()?, point.get(0)?.as_f64()?)))
+        .collect::<Option<Vec<_>>>()?;
+
+    (points.len() >= 2).then_some(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wkt_line_string() {
+        let points = parse_wkt_line_string("LINESTRING (3.7 51.0, 3.71 51.0,3.71 51.01)").unwrap();
+        assert_eq!(points, vec![(51.0, 3.7), (51.0, 3.71), (51.01, 3.71)]);
+
+        assert!(parse_wkt_line_string("LINESTRING (3.7 51.0)").is_none());
+        assert!(parse_wkt_line_string("POINT (3.7 51.0)").is_none());
+        assert!(parse_wkt_line_string("LINESTRING (3.7 x, 3.71 51.0)").is_none());
+    }
+}
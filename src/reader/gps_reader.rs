@@ -0,0 +1,133 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, ErrorKind};
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::core::matching::GpsPoint;
+
+//reads all track points of a gpx file, in the order they appear in the file
+pub fn read_gpx(file_name: &str) -> Result<Vec<GpsPoint>, io::Error> {
+    let mut reader = Reader::from_reader(BufReader::new(File::open(file_name)?));
+
+    let mut points = vec![];
+    let mut buf = vec![];
+    let mut in_time = false;
+
+    loop {
+        match reader.read_event_into(&mut buf).map_err(invalid_data)? {
+            Event::Start(e) if e.name().as_ref() == b"trkpt" => points.push(parse_track_point(&e)?),
+            Event::Empty(e) if e.name().as_ref() == b"trkpt" => points.push(parse_track_point(&e)?),
+            Event::Start(e) if e.name().as_ref() == b"time" => in_time = true,
+            Event::End(e) if e.name().as_ref() == b"time" => in_time = false,
+            Event::Text(t) if in_time => {
+                //the time of the metadata comes before any track point, so only use the time if there is a point
+                if let Some(point) = points.last_mut() {
+                    let text = t.unescape().map_err(invalid_data)?;
+                    point.time = parse_iso_time(text.trim());
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(points)
+}
+
+//reads a csv file with lines: time,lat,lon. The time is in seconds since the unix epoch and can be empty
+//a header line is allowed
+pub fn read_csv(file_name: &str) -> Result<Vec<GpsPoint>, io::Error> {
+    let reader = BufReader::new(File::open(file_name)?);
+
+    let mut points = vec![];
+    for (line_nr, line) in reader.lines().enumerate() {
+        let line = line?;
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        if line.trim().is_empty() || (line_nr == 0 && fields.get(1).is_some_and(|f| f.parse::<f64>().is_err())) {
+            continue;
+        }
+
+        if fields.len() < 3 {
+            return Err(io::Error::new(ErrorKind::InvalidData, format!("line {} doesn't have 3 fields", line_nr + 1)));
+        }
+
+        let parse = |field: &str| {
+            field
+                .parse::<f64>()
+                .map_err(|_| io::Error::new(ErrorKind::InvalidData, format!("invalid number {} on line {}", field, line_nr + 1)))
+        };
+
+        points.push(GpsPoint {
+            time: if fields[0].is_empty() { None } else { Some(parse(fields[0])?) },
+            lat: parse(fields[1])?,
+            lon: parse(fields[2])?,
+        });
+    }
+
+    Ok(points)
+}
+
+fn parse_track_point(e: &BytesStart) -> Result<GpsPoint, io::Error> {
+    let mut lat = None;
+    let mut lon = None;
+    for attribute in e.attributes() {
+        let attribute = attribute.map_err(invalid_data)?;
+        let value = attribute.unescape_value().map_err(invalid_data)?.parse::<f64>().ok();
+        match attribute.key.as_ref() {
+            b"lat" => lat = value,
+            b"lon" => lon = value,
+            _ => {}
+        }
+    }
+
+    match (lat, lon) {
+        (Some(lat), Some(lon)) => Ok(GpsPoint { lat, lon, time: None }),
+        _ => Err(io::Error::new(ErrorKind::InvalidData, "track point without valid lat and lon")),
+    }
+}
+
+//parses times like 2024-03-01T08:30:00Z or 2024-03-01T08:30:00.250Z to seconds since the unix epoch
+//only utc times are supported
+pub fn parse_iso_time(text: &str) -> Option<f64> {
+    let text = text.strip_suffix('Z')?;
+    let (date, time) = text.split_once('T')?;
+
+    let mut date_parts = date.split('-').map(|p| p.parse::<i64>());
+    let (year, month, day) = (date_parts.next()?.ok()?, date_parts.next()?.ok()?, date_parts.next()?.ok()?);
+
+    let mut time_parts = time.split(':');
+    let hours = time_parts.next()?.parse::<f64>().ok()?;
+    let minutes = time_parts.next()?.parse::<f64>().ok()?;
+    let seconds = time_parts.next()?.parse::<f64>().ok()?;
+
+    Some(days_from_civil(year, month, day) as f64 * 86400.0 + hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+//number of days since 1970-01-01 in the proleptic gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+fn invalid_data<E: std::error::Error + Send + Sync + 'static>(e: E) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iso_time() {
+        assert_eq!(parse_iso_time("1970-01-01T00:00:00Z"), Some(0.0));
+        assert_eq!(parse_iso_time("2024-03-01T08:30:00.5Z"), Some(1709281800.5));
+        assert_eq!(parse_iso_time("2024-03-01T08:30:00+01:00"), None);
+    }
+}
@@ -0,0 +1,98 @@
+use std::io::{self, ErrorKind};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+const CHUNK_BITS: u64 = 1 << 24; //2 MB per chunk
+const TOTAL_BITS: u64 = 1 << 40; //more than enough for the node ids of the planet
+const NR_CHUNKS: usize = (TOTAL_BITS / CHUNK_BITS) as usize;
+
+/// A set of node ids with one bit per possible id, that can be filled by many threads at once
+/// chunks are only allocated when an id inside them is inserted, so the memory is bounded by the largest id instead of by the number of ids
+/// negative ids (new objects of editors) are stored at the end
+pub struct NodeBitset {
+    chunks: Vec<OnceLock<Box<[AtomicU64]>>>,
+}
+
+impl Default for NodeBitset {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NodeBitset {
+    pub fn new() -> Self {
+        NodeBitset {
+            chunks: (0..NR_CHUNKS).map(|_| OnceLock::new()).collect(),
+        }
+    }
+
+    //(chunk, word inside the chunk, mask inside the word), None for ids that don't fit in the bitset
+    fn position(id: i64) -> Option<(usize, usize, u64)> {
+        let bit = if id >= 0 {
+            id as u64
+        } else {
+            TOTAL_BITS.checked_sub(id.unsigned_abs())?
+        };
+        if bit >= TOTAL_BITS {
+            return None;
+        }
+
+        let chunk = (bit / CHUNK_BITS) as usize;
+        let offset = bit % CHUNK_BITS;
+        Some((chunk, (offset / 64) as usize, 1 << (offset % 64)))
+    }
+
+    //returns if the id was already in the set, ids of 2^40 and more (or below -2^40) can't be stored
+    pub fn insert(&self, id: i64) -> Result<bool, io::Error> {
+        let (chunk, word, mask) = Self::position(id)
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, format!("node id {} is too large for IdMapping::Bitsets", id)))?;
+        let words = self.chunks[chunk].get_or_init(|| (0..CHUNK_BITS / 64).map(|_| AtomicU64::new(0)).collect());
+
+        Ok(words[word].fetch_or(mask, Ordering::Relaxed) & mask != 0)
+    }
+
+    //an id that doesn't fit was never inserted
+    pub fn contains(&self, id: i64) -> bool {
+        Self::position(id).is_some_and(|(chunk, word, mask)| {
+            self.chunks[chunk]
+                .get()
+                .is_some_and(|words| words[word].load(Ordering::Relaxed) & mask != 0)
+        })
+    }
+
+    //in bytes
+    pub fn allocated(&self) -> usize {
+        self.chunks.iter().filter(|c| c.get().is_some()).count() * (CHUNK_BITS / 8) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitset() {
+        let bitset = NodeBitset::new();
+        assert_eq!(bitset.allocated(), 0);
+
+        for id in [0, 63, 64, CHUNK_BITS as i64 * 3 + 5, 12_000_000_000, -1, -42] {
+            assert!(!bitset.contains(id));
+            assert!(!bitset.insert(id).unwrap());
+            assert!(bitset.contains(id));
+            assert!(bitset.insert(id).unwrap());
+        }
+
+        assert!(!bitset.contains(1));
+        assert!(!bitset.contains(-2));
+        assert_eq!(bitset.allocated(), 4 * (CHUNK_BITS / 8) as usize);
+    }
+
+    #[test]
+    fn test_too_large() {
+        let bitset = NodeBitset::new();
+        for id in [TOTAL_BITS as i64, i64::MAX, i64::MIN] {
+            assert_eq!(bitset.insert(id).unwrap_err().kind(), ErrorKind::InvalidData);
+            assert!(!bitset.contains(id));
+        }
+    }
+}
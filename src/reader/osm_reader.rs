@@ -1,149 +1,627 @@
 use std::collections::HashMap;
+use std::io;
+use std::sync::mpsc;
+use std::thread;
 
+use super::boundary::Boundary;
+use super::node_bitset::NodeBitset;
+use super::osm_xml_reader::{is_osm_xml, read_osm_xml, XmlElement};
+use super::profile::Profile;
 use super::vehicle_permissions::*;
 use crate::{
+    core::edge::{EdgeAttributes, RoadClass, VehicleAccess, VehicleTypes},
     core::{Edge, Graph, Node, StandardGraph},
-    reader::tags_map::convert_tags_to_map,
+    reader::tags_map::{convert_tags_to_map, TagsMap},
 };
 
 use osmpbf::{Element, ElementReader};
+use rayon::prelude::*;
 
 use geoutils::Location;
 
-enum NodeType {
-    TowerNode, //end of a way or the middle of a way that connects to another way
-    ShapeNode, //middle of a way that's just there for shape
+//a barrier in the middle of a way becomes 2 nodes with an edge of length 0 for the barrier in between
+//a barrier on a junction or at the end of a way blocks or slows down every edge in the direction that enters the node,
+//so a route through the node passes it once, whatever ways it uses
+pub(super) struct Barrier {
+    passable: Vec<VehicleTypes>, //the vehicle types of the profiles that can pass
+    time_penalty: f64,
 }
 
+//everything the edges of a way need
+pub(super) struct WayInfo {
+    attributes: EdgeAttributes,
+    vehicle_access: Vec<(VehicleTypes, VehicleAccess)>,
+    duration: Option<f64>, //seconds needed for the whole way, from the duration tag of ferries
+}
+
+//a way that at least one profile can use
+pub(super) struct UsefulWay {
+    pub info: WayInfo,
+    pub refs: Vec<i64>, //only the tower nodes after categorizing, the other nodes are just there for shape
+}
+
+//a node or way, whatever the format of the file
+pub(super) enum OsmElement<'e> {
+    Node { id: i64, lat: f64, lon: f64, tags: TagsMap<'e> },
+    Way { id: i64, tags: TagsMap<'e>, refs: Vec<i64> },
+}
+
+//(from, to, distance, (barrier passed in the forward direction, barrier passed in the backward direction))
+pub(super) type Segment<'b> = (usize, usize, f64, (Option<&'b Barrier>, Option<&'b Barrier>));
+
+//a node that is in the graph: its index, location and for a barrier in the middle of a way the index of its copy and the barrier
+pub(super) struct TowerNode<'b> {
+    pub index: usize,
+    pub location: Location,
+    pub barrier: Option<(usize, &'b Barrier)>,
+    pub junction_barrier: Option<&'b Barrier>, //a barrier on a junction or the end of a way, for the edges that enter the node
+}
+
+//the number of parsed ways that can wait to be added to the graph with IdMapping::Bitsets
+const WAYS_IN_FLIGHT: usize = 1024;
+
+/// How the reader keeps track of the node ids of the useful ways
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum IdMapping {
+    /// keeps the useful ways in memory and sorts all their node ids, fast but the memory grows with the size of the file
+    #[default]
+    SortedArrays,
+    /// one bit per possible node id to find the tower nodes, the ways are read again instead of kept in memory
+    /// the memory is bounded by the largest node id (about 3 GB for the planet), at the cost of reading the file once more
+    Bitsets,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ReaderOptions {
+    pub id_mapping: IdMapping,
+    pub boundary: Option<Boundary>, //only import the part of the file inside the boundary, see Boundary::from_bbox and read_poly
+}
+
+//a set of node ids, stored in the way that the id mapping asks for
+enum NodeIds {
+    Sorted(Vec<i64>),
+    Bitset(NodeBitset),
+}
+
+impl NodeIds {
+    fn contains(&self, node_id: i64) -> bool {
+        match self {
+            NodeIds::Sorted(ids) => ids.binary_search(&node_id).is_ok(),
+            NodeIds::Bitset(bitset) => bitset.contains(node_id),
+        }
+    }
+}
+
+/// Decodes the blobs of the file in parallel every time it is read:
+/// the first pass finds the tower nodes and barriers, the second pass reads the coordinates of the tower nodes
+/// and with IdMapping::Bitsets a third pass reads the ways again. With a boundary the nodes inside it are read before all that
 pub struct OsmReader<'a> {
     file_name: &'a str,
+    profiles: Vec<Profile>,
+    options: ReaderOptions,
 
-    node_types: HashMap<i64, NodeType>,          // from node ID to nodetype
-    way_permissions: HashMap<i64, (bool, bool)>, //from way id to
-    nr_useful_nodes: usize,
+    ways: Vec<UsefulWay>,                     //empty with IdMapping::Bitsets
+    inside_nodes: Option<NodeIds>,            //the nodes inside the boundary, None without a boundary
+    tower_nodes: NodeIds,                     //the nodes that become graph nodes: ends of ways, junctions and barriers
+    barriers: HashMap<i64, Barrier>,          //from node id to the barrier, only for barriers in the middle of a way
+    junction_barriers: HashMap<i64, Barrier>, //barriers on junctions and ends of ways
 }
 
 impl<'a> OsmReader<'a> {
-    pub fn new(file_name: &'a str) -> Result<Self, osmpbf::Error> {
-        let mut reader = OsmReader {
-            file_name,
-            node_types: HashMap::new(),
-            way_permissions: HashMap::new(),
-            nr_useful_nodes: 0,
-        };
-
-        reader.categorize_nodes()?;
-        Ok(reader)
+    //reads the graph for cars
+    pub fn new(file_name: &'a str) -> Result<Self, io::Error> {
+        Self::with_profiles(file_name, vec![Profile::car()])
     }
 
-    pub fn read_graph(&self) -> Result<impl Graph, osmpbf::Error> {
-        let reader = ElementReader::from_path(self.file_name)?;
+    //every profile must have a different vehicle type
+    pub fn with_profiles(file_name: &'a str, profiles: Vec<Profile>) -> Result<Self, io::Error> {
+        Self::with_options(file_name, profiles, ReaderOptions::default())
+    }
 
-        let mut g = StandardGraph::new(self.nr_useful_nodes);
-        let mut curr_node_index = 0; //the index inside the graph
+    pub fn with_options(file_name: &'a str, profiles: Vec<Profile>, options: ReaderOptions) -> Result<Self, io::Error> {
+        let mut reader = Self::uncategorized(file_name, profiles, options);
 
-        let mut nodes_map: HashMap<i64, usize> = HashMap::new();
-        let mut nr_ways = 0;
+        if let Some(boundary) = &reader.options.boundary {
+            reader.inside_nodes = Some(reader.find_inside_nodes(boundary)?);
+        }
+        match reader.options.id_mapping {
+            IdMapping::SortedArrays => reader.categorize_nodes()?,
+            IdMapping::Bitsets => reader.categorize_nodes_with_bitsets()?,
+        }
+        Ok(reader)
+    }
 
-        // ways always come after nodes
-        reader.for_each(|element| match element {
-            Element::Way(way) => {
-                nr_ways += 1;
+    //without reading the file, for the functions that only need the profiles
+    pub(super) fn uncategorized(file_name: &'a str, profiles: Vec<Profile>, options: ReaderOptions) -> Self {
+        OsmReader {
+            file_name,
+            profiles,
+            options,
+            ways: vec![],
+            inside_nodes: None,
+            tower_nodes: NodeIds::Sorted(vec![]),
+            barriers: HashMap::new(),
+            junction_barriers: HashMap::new(),
+        }
+    }
 
-                let (car_fwd, car_bwd) = self.way_permissions.get(&way.id()).unwrap_or(&(false, false));
+    fn find_inside_nodes(&self, boundary: &Boundary) -> Result<NodeIds, io::Error> {
+        let nodes = match self.options.id_mapping {
+            IdMapping::SortedArrays => {
+                let mut ids = self.map_reduce(
+                    |element| match element {
+                        OsmElement::Node { id, lat, lon, .. } if boundary.contains(lat, lon) => vec![id],
+                        _ => vec![],
+                    },
+                    Vec::new,
+                    |mut a, b| {
+                        a.extend(b);
+                        a
+                    },
+                )?;
+                ids.par_sort_unstable();
+                NodeIds::Sorted(ids)
+            }
+            IdMapping::Bitsets => {
+                let bitset = NodeBitset::new();
+                self.map_reduce(
+                    |element| match element {
+                        OsmElement::Node { id, lat, lon, .. } if boundary.contains(lat, lon) => bitset.insert(id).map(|_| ()),
+                        _ => Ok(()),
+                    },
+                    || Ok(()),
+                    |a, b| a.and(b),
+                )??;
+                NodeIds::Bitset(bitset)
+            }
+        };
 
-                if *car_fwd || *car_bwd {
-                    let mut last_node: isize = -1;
-                    let mut last_location = Location::new(0, 0);
-                    let mut curr_location = Location::new(0, 0);
+        Ok(nodes)
+    }
 
-                    for node_id in way.refs() {
-                        if let Some(&curr_node) = nodes_map.get(&node_id) {
-                            if let Some(n) = g.get_node(curr_node) {
-                                curr_location = Location::new(n.lat, n.lon);
-                            }
+    pub fn read_graph(&self) -> Result<impl Graph, io::Error> {
+        //(id, lat, lon)
+        let mut coordinates = self.map_reduce(
+            |element| match element {
+                OsmElement::Node { id, lat, lon, .. } if self.tower_nodes.contains(id) => vec![(id, lat, lon)],
+                _ => vec![],
+            },
+            Vec::new,
+            |mut a, b| {
+                a.extend(b);
+                a
+            },
+        )?;
+        coordinates.par_sort_unstable_by_key(|(node_id, _, _)| *node_id);
+        coordinates.dedup_by_key(|(node_id, _, _)| *node_id);
 
-                            if !self.node_types.get(&node_id).is_some_and(|x| matches!(x, NodeType::TowerNode)) {
-                                continue;
-                            }
+        //the sorted ids of the tower nodes in the file, with the index inside the graph and the location at the same position
+        let mut g = StandardGraph::new(coordinates.len() + self.barriers.len());
+        let mut node_ids = Vec::with_capacity(coordinates.len());
+        let mut graph_nodes = Vec::with_capacity(coordinates.len());
+        for (node_id, lat, lon) in coordinates {
+            node_ids.push(node_id);
+            graph_nodes.push((g.get_nr_nodes(), Location::new(lat, lon)));
 
-                            if last_node >= 0 {
-                                //cast to usize is safe
-                                let dist = last_location.distance_to(&curr_location).unwrap().meters();
+            g.add_node(Node::new(node_id, lat, lon));
+            if self.barriers.contains_key(&node_id) {
+                //the copy is always the next index
+                g.add_node(Node::new(node_id, lat, lon));
+            }
+        }
 
-                                let edge = Edge::new(dist, *car_fwd, *car_bwd);
-                                g.add_edge(last_node as usize, curr_node, edge);
-                            }
+        //the distances are calculated in parallel, the graph itself can only be built by one thread
+        let nr_ways = match self.options.id_mapping {
+            IdMapping::SortedArrays => {
+                let ways: Vec<_> = self
+                    .ways
+                    .par_iter()
+                    .map(|way| create_segments(way, |node_id| self.find_tower_node(node_id, &node_ids, &graph_nodes)))
+                    .collect();
 
-                            last_node = curr_node as isize;
-                            last_location = curr_location;
-                        }
-                    }
+                for (way, (segments, vehicle_access)) in self.ways.iter().zip(ways) {
+                    add_edges(&mut g, &way.info, segments, vehicle_access);
                 }
+                self.ways.len()
             }
-            Element::Node(_) => {}
-            Element::DenseNode(node) => {
-                if self.node_types.get(&node.id).is_some_and(|x| matches!(x, NodeType::TowerNode)) {
-                    g.add_node(Node::new(node.id, node.lat(), node.lon()));
-                    nodes_map.insert(node.id, curr_node_index);
-                    curr_node_index += 1;
-                }
+            IdMapping::Bitsets => {
+                //the edges are streamed into the graph, so that only a few ways are in memory at once
+                let (sender, receiver) = mpsc::sync_channel(WAYS_IN_FLIGHT);
+                thread::scope(|scope| {
+                    let parsing = scope.spawn(move || {
+                        self.map_reduce(
+                            |element| match element {
+                                OsmElement::Way { id, tags, refs } => {
+                                    let ways = self.get_useful_way(id, &tags, refs);
+                                    let nr_ways = ways.len();
+                                    for way in ways {
+                                        let (segments, vehicle_access) =
+                                            create_segments(&way, |node_id| self.find_tower_node(node_id, &node_ids, &graph_nodes));
+                                        //the receiver only stops when the graph is done
+                                        let _ = sender.send((way.info, segments, vehicle_access));
+                                    }
+                                    nr_ways
+                                }
+                                _ => 0,
+                            },
+                            || 0,
+                            |a, b| a + b,
+                        )
+                    });
+
+                    for (info, segments, vehicle_access) in receiver {
+                        add_edges(&mut g, &info, segments, vehicle_access);
+                    }
+                    parsing.join().expect("the thread that parses the ways panicked")
+                })?
             }
-            Element::Relation(_) => {}
-        })?;
+        };
 
         println!("nr ways parsed: {}", nr_ways);
 
         Result::Ok(g)
     }
 
-    pub fn categorize_nodes(&mut self) -> Result<(), osmpbf::Error> {
-        let reader = ElementReader::from_path(self.file_name)?;
+    //a node of the way that is in node_ids, with the copy at the next index for barriers
+    fn find_tower_node(&self, node_id: i64, node_ids: &[i64], graph_nodes: &[(usize, Location)]) -> Option<TowerNode<'_>> {
+        let (index, location) = graph_nodes[node_ids.binary_search(&node_id).ok()?];
+        Some(TowerNode {
+            index,
+            location,
+            barrier: self.barriers.get(&node_id).map(|b| (index + 1, b)),
+            junction_barrier: self.junction_barriers.get(&node_id),
+        })
+    }
 
-        let mut nr_useful_ways = 0;
-
-        reader.for_each(|element| {
-            match element {
-                Element::Way(way) => {
-                    let tags_map = convert_tags_to_map(way.tags());
-                    let (car_fwd, car_bwd) = is_car_allowed(&tags_map); // in future we might want to support more than just car
-
-                    self.way_permissions.insert(way.id(), (car_fwd, car_bwd));
-
-                    if car_fwd || car_bwd {
-                        nr_useful_ways += 1;
-                        let mut first = true;
-                        let mut last = -1;
-                        for node_id in way.refs() {
-                            if first {
-                                self.node_types.insert(node_id, NodeType::TowerNode);
-                                self.nr_useful_nodes += 1;
-                                first = false;
-                            }
+    pub fn categorize_nodes(&mut self) -> Result<(), io::Error> {
+        let (mut ways, mut barriers) = self.map_reduce(
+            |element| match element {
+                OsmElement::Way { id, tags, refs } => (self.get_useful_way(id, &tags, refs), vec![]),
+                OsmElement::Node { id, tags, .. } => (vec![], self.get_barriers(id, &tags)),
+            },
+            || (vec![], vec![]),
+            |(mut ways, mut barriers), (other_ways, other_barriers)| {
+                ways.extend(other_ways);
+                barriers.extend(other_barriers);
+                (ways, barriers)
+            },
+        )?;
 
-                            self.node_types
-                                .entry(node_id)
-                                .and_modify(|e| {
-                                    *e = NodeType::TowerNode;
-                                    self.nr_useful_nodes += 1;
-                                })
-                                .or_insert(NodeType::ShapeNode);
-                            last = node_id;
-                        }
+        println!("nr useful ways: {}", ways.len());
+
+        //sorting all node ids of the useful ways replaces a map with an entry for each of them
+        let mut refs: Vec<i64> = ways.iter().flat_map(|way| way.refs.iter().copied()).collect();
+        refs.par_sort_unstable();
+
+        //nodes that are used more than once are junctions
+        let mut tower_nodes: Vec<i64> = refs.windows(2).filter(|w| w[0] == w[1]).map(|w| w[0]).collect();
+        tower_nodes.extend(ways.iter().flat_map(|way| [way.refs.first(), way.refs.last()]).flatten());
 
-                        if last != -1 {
-                            self.node_types.insert(last, NodeType::TowerNode);
+        //only barriers in the middle of a single way split it
+        tower_nodes.par_sort_unstable();
+        tower_nodes.dedup();
+        barriers.retain(|(node_id, _)| refs.binary_search(node_id).is_ok());
+        drop(refs);
+        let (barriers, junction_barriers): (Vec<_>, Vec<_>) =
+            barriers.into_iter().partition(|(node_id, _)| tower_nodes.binary_search(node_id).is_err());
+
+        tower_nodes.extend(barriers.iter().map(|(node_id, _)| *node_id));
+        tower_nodes.par_sort_unstable();
+        tower_nodes.dedup();
+        println!("nr tower nodes: {}, nr barriers: {}", tower_nodes.len(), barriers.len());
+
+        ways.par_iter_mut()
+            .for_each(|way| way.refs.retain(|node_id| tower_nodes.binary_search(node_id).is_ok()));
+
+        self.ways = ways;
+        self.tower_nodes = NodeIds::Sorted(tower_nodes);
+        self.barriers = barriers.into_iter().collect();
+        self.junction_barriers = junction_barriers.into_iter().collect();
+
+        Ok(())
+    }
+
+    //same result as categorize_nodes, but without keeping the ways or their node ids in memory
+    fn categorize_nodes_with_bitsets(&mut self) -> Result<(), io::Error> {
+        let seen = NodeBitset::new();
+        let tower_nodes = NodeBitset::new();
+        let (nr_ways, barriers) = self.map_reduce(
+            |element| match element {
+                OsmElement::Way { id, tags, refs } => {
+                    let ways = self.get_useful_way(id, &tags, refs);
+                    for way in ways.iter() {
+                        //nodes that are used more than once are junctions
+                        for node_id in way.refs.iter() {
+                            if seen.insert(*node_id)? {
+                                tower_nodes.insert(*node_id)?;
+                            }
+                        }
+                        for node_id in [way.refs.first(), way.refs.last()].into_iter().flatten() {
+                            tower_nodes.insert(*node_id)?;
                         }
                     }
+                    Ok((ways.len(), vec![]))
                 }
-                Element::Node(_) => {}
-                Element::DenseNode(_) => {}
-                Element::Relation(_) => {}
-            }
-        })?;
+                OsmElement::Node { id, tags, .. } => Ok((0, self.get_barriers(id, &tags))),
+            },
+            || Ok((0, vec![])),
+            |a: Result<_, io::Error>, b| {
+                let ((nr_ways, mut barriers), (other_nr_ways, other_barriers)) = (a?, b?);
+                barriers.extend(other_barriers);
+                Ok((nr_ways + other_nr_ways, barriers))
+            },
+        )??;
+
+        println!("nr useful ways: {}", nr_ways);
+
+        //only barriers in the middle of a single way split it
+        let (barriers, junction_barriers): (Vec<_>, Vec<_>) = barriers
+            .into_iter()
+            .filter(|(node_id, _)| seen.contains(*node_id))
+            .partition(|(node_id, _)| !tower_nodes.contains(*node_id));
+        drop(seen);
+
+        for (node_id, _) in barriers.iter() {
+            tower_nodes.insert(*node_id)?;
+        }
+        println!("nr barriers: {}, memory used by the tower nodes: {} MB", barriers.len(), tower_nodes.allocated() / (1024 * 1024));
 
-        println!("nr useful ways: {}", nr_useful_ways);
+        self.tower_nodes = NodeIds::Bitset(tower_nodes);
+        self.barriers = barriers.into_iter().collect();
+        self.junction_barriers = junction_barriers.into_iter().collect();
 
         Ok(())
     }
+
+    //calls map for every node and way of the file. The blobs of pbf files are decoded in parallel, xml files are read by one thread
+    pub(super) fn map_reduce<T, M, I, R>(&self, map: M, identity: I, reduce: R) -> Result<T, io::Error>
+    where
+        T: Send,
+        M: Fn(OsmElement) -> T + Sync + Send,
+        I: Fn() -> T + Sync + Send,
+        R: Fn(T, T) -> T + Sync + Send,
+    {
+        if is_osm_xml(self.file_name) {
+            let mut result = Some(identity());
+            read_osm_xml(self.file_name, |element| {
+                let mapped = match element {
+                    XmlElement::Node { id, lat, lon, tags } => map(OsmElement::Node {
+                        id,
+                        lat,
+                        lon,
+                        tags: convert_tags_to_map(tags.iter().map(|(k, v)| (k.as_str(), v.as_str()))),
+                    }),
+                    XmlElement::Way { id, refs, tags } => map(OsmElement::Way {
+                        id,
+                        tags: convert_tags_to_map(tags.iter().map(|(k, v)| (k.as_str(), v.as_str()))),
+                        refs,
+                    }),
+                };
+                result = result.take().map(|r| reduce(r, mapped));
+            })?;
+
+            return Ok(result.unwrap());
+        }
+
+        //some tools write plain nodes instead of dense nodes
+        let reader = ElementReader::from_path(self.file_name)?;
+        let result = reader.par_map_reduce(
+            |element| match element {
+                Element::DenseNode(node) => map(OsmElement::Node {
+                    id: node.id,
+                    lat: node.lat(),
+                    lon: node.lon(),
+                    tags: convert_tags_to_map(node.tags()),
+                }),
+                Element::Node(node) => map(OsmElement::Node {
+                    id: node.id(),
+                    lat: node.lat(),
+                    lon: node.lon(),
+                    tags: convert_tags_to_map(node.tags()),
+                }),
+                Element::Way(way) => map(OsmElement::Way {
+                    id: way.id(),
+                    tags: convert_tags_to_map(way.tags()),
+                    refs: way.refs().collect(),
+                }),
+                Element::Relation(_) => identity(),
+            },
+            &identity,
+            &reduce,
+        )?;
+
+        Ok(result)
+    }
+
+    pub(super) fn get_useful_way(&self, way_id: i64, tags: &TagsMap, refs: Vec<i64>) -> Vec<UsefulWay> {
+        let vehicle_access = self.get_vehicle_access(tags);
+        if vehicle_access.is_empty() {
+            return vec![];
+        }
+
+        let nr_refs = refs.len();
+        let parts = self.clip(refs);
+        if parts.is_empty() {
+            return vec![];
+        }
+
+        //the duration is only known for the whole way
+        let clipped = parts.len() > 1 || parts[0].len() < nr_refs;
+        let duration = if is_ferry(tags) && !clipped {
+            tags.0.get("duration").and_then(|d| parse_duration(d))
+        } else {
+            None
+        };
+
+        let attributes = get_edge_attributes(way_id, tags);
+        parts
+            .into_iter()
+            .map(|refs| UsefulWay {
+                info: WayInfo {
+                    attributes: attributes.clone(),
+                    vehicle_access: vehicle_access.clone(),
+                    duration,
+                },
+                refs,
+            })
+            .collect()
+    }
+
+    //the parts of the way inside the boundary, every part continues up to the first node outside
+    fn clip(&self, refs: Vec<i64>) -> Vec<Vec<i64>> {
+        let Some(inside_nodes) = &self.inside_nodes else {
+            return vec![refs];
+        };
+
+        let inside: Vec<bool> = refs.iter().map(|node_id| inside_nodes.contains(*node_id)).collect();
+        let mut parts = vec![];
+        let mut part = vec![];
+        for (i, node_id) in refs.into_iter().enumerate() {
+            if inside[i] || (i > 0 && inside[i - 1]) || inside.get(i + 1) == Some(&true) {
+                part.push(node_id);
+            } else if !part.is_empty() {
+                parts.push(std::mem::take(&mut part));
+            }
+        }
+        parts.push(part);
+
+        parts.retain(|part| part.len() >= 2);
+        parts
+    }
+
+    //a vec, so that it can be collected by map_reduce
+    fn get_barriers(&self, node_id: i64, tags: &TagsMap) -> Vec<(i64, Barrier)> {
+        self.get_barrier(tags).map(|b| (node_id, b)).into_iter().collect()
+    }
+
+    //only barriers that block or slow down at least one profile
+    pub(super) fn get_barrier(&self, tags: &TagsMap) -> Option<Barrier> {
+        if !tags.has_key("barrier") {
+            return None;
+        }
+
+        let barrier = Barrier {
+            passable: self
+                .profiles
+                .iter()
+                .filter(|profile| is_barrier_passable(tags, profile))
+                .map(|profile| profile.vehicle_type)
+                .collect(),
+            time_penalty: get_barrier_penalty(tags),
+        };
+
+        (barrier.passable.len() < self.profiles.len() || barrier.time_penalty > 0.0).then_some(barrier)
+    }
+
+    //the access of every profile that can use the way in at least one direction, possibly only at certain times. Speeds in m/s
+    fn get_vehicle_access(&self, tags: &TagsMap) -> Vec<(VehicleTypes, VehicleAccess)> {
+        self.profiles
+            .iter()
+            .filter_map(|profile| {
+                let (forward, backward) = is_allowed(tags, profile);
+                let conditions = get_conditional_restrictions(tags, profile);
+                if !forward && !backward && !conditions.iter().any(|c| c.grants_access()) {
+                    return None;
+                }
+
+                let speed = get_speed(tags, profile) / 3.6;
+                let access = VehicleAccess {
+                    forward,
+                    backward,
+                    forward_speed: speed,
+                    backward_speed: speed,
+                    conditions,
+                };
+                Some((profile.vehicle_type, access))
+            })
+            .collect()
+    }
+}
+
+//the edges of the way and the vehicle access of those edges, nodes that aren't tower nodes are only there for shape
+pub(super) fn create_segments<'b>(
+    way: &UsefulWay,
+    find_tower_node: impl Fn(i64) -> Option<TowerNode<'b>>,
+) -> (Vec<Segment<'b>>, Vec<(VehicleTypes, VehicleAccess)>) {
+    let mut segments = vec![];
+    let mut last: Option<(usize, Location, Option<&Barrier>)> = None;
+
+    for tower_node in way.refs.iter().filter_map(|node_id| find_tower_node(*node_id)) {
+        if let Some((last_node, last_location, last_barrier)) = last {
+            let dist = last_location.distance_to(&tower_node.location).unwrap().meters();
+            segments.push((last_node, tower_node.index, dist, (tower_node.junction_barrier, last_barrier)));
+        }
+        last = Some((tower_node.index, tower_node.location, tower_node.junction_barrier));
+
+        //the way continues from the copy of the barrier node
+        if let Some((copy, barrier)) = tower_node.barrier {
+            segments.push((tower_node.index, copy, 0.0, (Some(barrier), Some(barrier))));
+            last = Some((copy, tower_node.location, None));
+        }
+    }
+
+    let vehicle_access = match way.info.duration {
+        //the duration is for the whole way, so every edge gets the average speed
+        Some(duration) => {
+            let length: f64 = segments.iter().map(|(_, _, dist, _)| dist).sum();
+            with_speed(&way.info.vehicle_access, length / duration)
+        }
+        None => way.info.vehicle_access.clone(),
+    };
+
+    (segments, vehicle_access)
+}
+
+pub(super) fn add_edges(g: &mut StandardGraph, info: &WayInfo, segments: Vec<Segment<'_>>, vehicle_access: Vec<(VehicleTypes, VehicleAccess)>) {
+    for (from, to, dist, (forward_barrier, backward_barrier)) in segments {
+        let edge = match (forward_barrier, backward_barrier) {
+            (None, None) => Edge::with_attributes(dist, info.attributes.clone(), vehicle_access.clone()),
+            _ => {
+                let blocks = |barrier: Option<&Barrier>, vehicle_type| barrier.is_some_and(|b| !b.passable.contains(vehicle_type));
+                let access = vehicle_access
+                    .iter()
+                    .map(|(vehicle_type, access)| {
+                        let (forward_blocked, backward_blocked) = (blocks(forward_barrier, vehicle_type), blocks(backward_barrier, vehicle_type));
+                        let mut access = access.clone();
+                        access.forward &= !forward_blocked;
+                        access.backward &= !backward_blocked;
+                        for condition in access.conditions.iter_mut() {
+                            condition.forward = condition.forward.filter(|_| !forward_blocked);
+                            condition.backward = condition.backward.filter(|_| !backward_blocked);
+                        }
+                        (*vehicle_type, access)
+                    })
+                    .collect();
+                let penalty = |barrier: Option<&Barrier>| barrier.map_or(0.0, |b| b.time_penalty);
+                Edge::with_time_penalties(dist, info.attributes.clone(), access, (penalty(forward_barrier), penalty(backward_barrier)))
+            }
+        };
+        g.add_edge(from, to, edge);
+    }
+}
+
+//speed in m/s
+fn with_speed(vehicle_access: &[(VehicleTypes, VehicleAccess)], speed: f64) -> Vec<(VehicleTypes, VehicleAccess)> {
+    vehicle_access
+        .iter()
+        .map(|(vehicle_type, access)| {
+            let access = VehicleAccess {
+                forward_speed: speed,
+                backward_speed: speed,
+                ..access.clone()
+            };
+            (*vehicle_type, access)
+        })
+        .collect()
+}
+
+fn get_edge_attributes(way_id: i64, tags: &TagsMap) -> EdgeAttributes {
+    EdgeAttributes {
+        road_class: tags.0.get("highway").map_or(RoadClass::Other, |h| RoadClass::from_highway(h)),
+        toll: ["toll", "toll:motorcar", "toll:hgv"].iter().any(|key| tags.tag_equals(key, "yes")),
+        ferry: is_ferry(tags),
+        way_id: Some(way_id),
+        restrictions: get_physical_restrictions(tags),
+        time_penalty: 0.0,
+    }
 }
@@ -0,0 +1,462 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
+
+use geoutils::Location;
+
+use super::osm_reader::{add_edges, create_segments, Barrier, OsmElement, OsmReader, ReaderOptions, TowerNode, UsefulWay};
+use super::osm_xml_reader::{read_osm_change, ChangeAction, XmlElement};
+use super::profile::Profile;
+use super::tags_map::{convert_tags_to_map, TagsMap};
+use crate::core::components::options::{AlgorithmType, ComponentsAlgorithmOptions};
+use crate::core::{Graph, Node, StandardGraph};
+
+//a node that is touched by a change is kept if a search from it reaches an untouched node of the main component in both directions,
+//or if it visits this many nodes, because then it is part of a large component anyway. A cut off part that is larger stays in the main component
+const SEARCH_LIMIT: usize = 10_000;
+
+//a node of a way that could become useful after a change
+struct WayNode {
+    location: Location,
+    barrier: Option<Barrier>,
+}
+
+//how the useful ways use a node, to know if it is a tower node
+#[derive(Default)]
+struct NodeUse {
+    refs: usize,
+    ends: usize,
+    ways: Vec<i64>,
+}
+
+//the ways and nodes as they are in osm after the changes that were applied
+#[derive(Default)]
+struct OsmData {
+    ways: HashMap<i64, UsefulWay>,
+    nodes: HashMap<i64, WayNode>,
+    uses: HashMap<i64, NodeUse>,
+    indices: HashMap<i64, (usize, Option<usize>)>, //graph index of every node that is or was a tower node, with the index of the copy for barriers
+}
+
+/// A graph that can be kept up to date with osmChange files (.osc) instead of being read again.
+/// It keeps the useful ways with all their nodes and the locations of the nodes of every highway and route,
+/// so that a change only has to include the changed objects. Graph indices never change:
+/// nodes that aren't needed anymore or that aren't in the main strongly connected component lose their edges instead
+pub struct UpdatableGraph<'a> {
+    reader: OsmReader<'a>, //only for the profiles
+    graph: StandardGraph,
+    osm: OsmData,
+    main: Vec<bool>, //for every graph index: part of the main component
+}
+
+impl<'a> UpdatableGraph<'a> {
+    //reads the whole file and keeps only the largest strongly connected component, like new_router does
+    pub fn new(file_name: &'a str, profiles: Vec<Profile>) -> Result<Self, io::Error> {
+        let reader = OsmReader::uncategorized(file_name, profiles, ReaderOptions::default());
+
+        //ways that aren't useful are kept as node ids, a change can make them useful
+        let (ways, mut node_ids) = reader.map_reduce(
+            |element| match element {
+                OsmElement::Way { id, tags, refs } if is_candidate(&tags) => {
+                    let ways: Vec<(i64, UsefulWay)> = reader.get_useful_way(id, &tags, refs.clone()).into_iter().map(|w| (id, w)).collect();
+                    (ways, refs)
+                }
+                _ => (vec![], vec![]),
+            },
+            || (vec![], vec![]),
+            |(mut ways, mut node_ids), (other_ways, other_node_ids)| {
+                ways.extend(other_ways);
+                node_ids.extend(other_node_ids);
+                (ways, node_ids)
+            },
+        )?;
+        node_ids.sort_unstable();
+        node_ids.dedup();
+
+        let nodes = reader.map_reduce(
+            |element| match element {
+                OsmElement::Node { id, lat, lon, tags } if node_ids.binary_search(&id).is_ok() => vec![(id, lat, lon, reader.get_barrier(&tags))],
+                _ => vec![],
+            },
+            Vec::new,
+            |mut a, b| {
+                a.extend(b);
+                a
+            },
+        )?;
+
+        let mut updatable = UpdatableGraph {
+            reader,
+            graph: StandardGraph::new(nodes.len()),
+            osm: OsmData::default(),
+            main: vec![],
+        };
+        for (id, lat, lon, barrier) in nodes {
+            let location = Location::new(lat, lon);
+            updatable.osm.nodes.insert(id, WayNode { location, barrier });
+        }
+        for (id, way) in ways {
+            updatable.osm.insert_way(id, way);
+        }
+
+        //the same order as the reader, so that the graph is the same
+        let mut tower_nodes: Vec<i64> = updatable.osm.uses.keys().copied().filter(|id| updatable.osm.is_tower_node(*id)).collect();
+        tower_nodes.sort_unstable();
+        for id in tower_nodes {
+            updatable.add_tower_node(id);
+        }
+
+        let mut way_ids: Vec<i64> = updatable.osm.ways.keys().copied().collect();
+        way_ids.sort_unstable();
+        updatable.add_ways(&way_ids);
+
+        updatable.keep_largest_component();
+        Ok(updatable)
+    }
+
+    pub fn graph(&self) -> &StandardGraph {
+        &self.graph
+    }
+
+    pub fn into_graph(self) -> StandardGraph {
+        self.graph
+    }
+
+    //the graph index of a tower node, also when it isn't in the main component
+    pub fn node_index(&self, osm_id: i64) -> Option<usize> {
+        self.osm
+            .is_tower_node(osm_id)
+            .then(|| self.osm.indices.get(&osm_id).map(|(index, _)| *index))
+            .flatten()
+    }
+
+    pub fn is_in_main_component(&self, node: usize) -> bool {
+        self.main.get(node).copied().unwrap_or(false)
+    }
+
+    /// Applies the creates, modifies and deletes of an .osc or .osc.bz2 file in the order of the file.
+    /// The edges of every way that uses a changed node or that is changed itself are removed and created again,
+    /// with the permissions and speeds of the new tags. The component filtering is only run around those edges:
+    /// a node is kept as soon as a search from it visits 10,000 nodes, so a part that a change cuts off stays in the main component
+    /// when it is larger than that. Only the locations of the nodes of highways and routes are known,
+    /// a way that uses another node has to include that node in the change, otherwise nothing is applied and an InvalidData error is returned
+    pub fn apply_changes(&mut self, file_name: &str) -> Result<(), io::Error> {
+        let mut changes = vec![];
+        read_osm_change(file_name, |action, element| changes.push((action, element)))?;
+        self.check_locations(&changes)?;
+
+        //nodes with a new location or new tags and the old and new nodes of changed ways
+        let mut changed_nodes = HashSet::new();
+        for (_, element) in changes.iter() {
+            match element {
+                XmlElement::Node { id, .. } => {
+                    changed_nodes.insert(*id);
+                }
+                XmlElement::Way { id, refs, .. } => {
+                    changed_nodes.extend(refs.iter().copied());
+                    changed_nodes.extend(self.osm.ways.get(id).iter().flat_map(|way| way.refs.iter().copied()));
+                }
+            }
+        }
+
+        //the edges of the ways through the changed nodes, as they are now
+        let mut touched = HashSet::new();
+        let old_segments: Vec<(usize, usize)> = self
+            .osm
+            .ways_with_nodes(&changed_nodes)
+            .iter()
+            .flat_map(|id| self.osm.segments(&self.osm.ways[id]))
+            .collect();
+        for (from, to) in old_segments {
+            self.graph.remove_edge(from, to);
+            touched.extend([from, to]);
+        }
+
+        for (action, element) in changes {
+            match (action, element) {
+                (ChangeAction::Delete, XmlElement::Node { id, .. }) => {
+                    self.osm.nodes.remove(&id);
+                }
+                (_, XmlElement::Node { id, lat, lon, tags }) => {
+                    let barrier = self.reader.get_barrier(&to_tags_map(&tags));
+                    self.osm.nodes.insert(
+                        id,
+                        WayNode {
+                            location: Location::new(lat, lon),
+                            barrier,
+                        },
+                    );
+                    if let Some((index, copy)) = self.osm.indices.get(&id) {
+                        for node in [Some(*index), *copy].into_iter().flatten() {
+                            self.graph.set_location(node, lat, lon);
+                        }
+                    }
+                }
+                (action, XmlElement::Way { id, refs, tags }) => {
+                    self.osm.remove_way(id);
+                    let tags = to_tags_map(&tags);
+                    if action != ChangeAction::Delete {
+                        if let Some(way) = self.reader.get_useful_way(id, &tags, refs).into_iter().next() {
+                            self.osm.insert_way(id, way);
+                        }
+                    }
+                }
+            }
+        }
+
+        //the ways are created again between their tower nodes, the ends of removed edges can have other ways that need them again
+        let mut affected_nodes = changed_nodes;
+        affected_nodes.extend(touched.iter().map(|node| self.graph.get_node(*node).unwrap().osm_id));
+        let affected_ways = self.osm.ways_with_nodes(&affected_nodes);
+
+        let mut new_tower_nodes: Vec<i64> = affected_ways
+            .iter()
+            .flat_map(|id| self.osm.ways[id].refs.iter().copied())
+            .filter(|id| self.osm.is_tower_node(*id))
+            .collect();
+        new_tower_nodes.sort_unstable();
+        new_tower_nodes.dedup();
+        for id in new_tower_nodes {
+            self.add_tower_node(id);
+        }
+
+        //nodes that were cut off also lost the edges of their other ways, those come back when the node can be connected again
+        let mut new_nodes = self.add_ways(&affected_ways);
+        let mut expanded = HashSet::new();
+        loop {
+            let cut_off: HashSet<i64> = new_nodes
+                .iter()
+                .filter(|node| !self.main[**node])
+                .map(|node| self.graph.get_node(*node).unwrap().osm_id)
+                .filter(|osm_id| expanded.insert(*osm_id))
+                .collect();
+            touched.extend(new_nodes);
+            if cut_off.is_empty() {
+                break;
+            }
+            new_nodes = self.add_ways(&self.osm.ways_with_nodes(&cut_off));
+        }
+
+        self.filter_components(touched);
+
+        Ok(())
+    }
+
+    //every node of a created or modified way needs a location, either from before or from the change itself
+    fn check_locations(&self, changes: &[(ChangeAction, XmlElement)]) -> Result<(), io::Error> {
+        let changed_nodes: HashSet<i64> = changes
+            .iter()
+            .filter_map(|(action, element)| match element {
+                XmlElement::Node { id, .. } if *action != ChangeAction::Delete => Some(*id),
+                _ => None,
+            })
+            .collect();
+
+        for (action, element) in changes {
+            if let (ChangeAction::Create | ChangeAction::Modify, XmlElement::Way { id, refs, .. }) = (action, element) {
+                if let Some(node_id) = refs
+                    .iter()
+                    .find(|node_id| !self.osm.nodes.contains_key(node_id) && !changed_nodes.contains(node_id))
+                {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("way {} uses node {} without a known location, add the node to the change", id, node_id),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    //a node keeps its index when it stops being a tower node, so it is only added once
+    fn add_tower_node(&mut self, id: i64) {
+        let location = self.osm.nodes[&id].location;
+        let new_node = |g: &mut StandardGraph, main: &mut Vec<bool>| {
+            g.add_node(Node::new(id, location.latitude(), location.longitude()));
+            main.push(false);
+            g.get_nr_nodes() - 1
+        };
+
+        let (index, copy) = match self.osm.indices.get(&id) {
+            Some(indices) => *indices,
+            None => (new_node(&mut self.graph, &mut self.main), None),
+        };
+        let copy = match copy {
+            None if self.osm.is_splitting_barrier(id) => Some(new_node(&mut self.graph, &mut self.main)),
+            copy => copy,
+        };
+        self.osm.indices.insert(id, (index, copy));
+    }
+
+    //returns the nodes of the added edges
+    fn add_ways(&mut self, way_ids: &[i64]) -> HashSet<usize> {
+        let mut nodes = HashSet::new();
+        for id in way_ids {
+            let way = &self.osm.ways[id];
+            let (segments, vehicle_access) = create_segments(way, |id| self.osm.find_tower_node(id));
+            nodes.extend(segments.iter().flat_map(|(from, to, _, _)| [*from, *to]));
+            add_edges(&mut self.graph, &way.info, segments, vehicle_access);
+        }
+        nodes
+    }
+
+    fn remove_edges(&mut self, node: usize) {
+        let mut adj_nodes = vec![];
+        self.graph.do_for_all_neighbors(node, false, |adj_node| adj_nodes.push(adj_node));
+        self.graph.do_for_all_neighbors(node, true, |adj_node| adj_nodes.push(adj_node));
+        for adj_node in adj_nodes {
+            self.graph.remove_edge(node, adj_node);
+        }
+    }
+
+    fn keep_largest_component(&mut self) {
+        let opts = ComponentsAlgorithmOptions::new(AlgorithmType::TARJAN);
+        let components = self.graph.get_strongly_connected_subgraphs(&opts);
+        let largest = components.into_iter().max_by_key(|c| c.len()).unwrap_or_default();
+
+        for node in 0..self.graph.get_nr_nodes() {
+            self.main[node] = largest.contains(&node);
+            if !self.main[node] {
+                self.remove_edges(node);
+            }
+        }
+    }
+
+    //removes the edges of the touched nodes that are cut off from the main component until the remaining ones are all connected to it
+    fn filter_components(&mut self, mut touched: HashSet<usize>) {
+        //without an untouched node there is nothing to search for, this only happens for small graphs
+        if !(0..self.main.len()).any(|node| self.main[node] && !touched.contains(&node)) {
+            self.keep_largest_component();
+            return;
+        }
+
+        loop {
+            let cut_off: Vec<usize> = touched
+                .iter()
+                .copied()
+                .filter(|node| !self.reaches_main(*node, false, &touched) || !self.reaches_main(*node, true, &touched))
+                .collect();
+            if cut_off.is_empty() {
+                break;
+            }
+
+            for node in cut_off {
+                self.remove_edges(node);
+                self.main[node] = false;
+                touched.remove(&node);
+            }
+        }
+
+        for node in touched {
+            self.main[node] = true;
+        }
+    }
+
+    fn reaches_main(&self, start: usize, reverse: bool, touched: &HashSet<usize>) -> bool {
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            if (self.main[node] && !touched.contains(&node)) || visited.len() >= SEARCH_LIMIT {
+                return true;
+            }
+
+            self.graph.do_for_all_neighbors(node, reverse, |adj_node| {
+                if visited.insert(adj_node) {
+                    queue.push_back(adj_node);
+                }
+            });
+        }
+
+        false
+    }
+}
+
+impl OsmData {
+    //the ids of the useful ways with at least one of the nodes, sorted so that the edges are always added in the same order
+    fn ways_with_nodes(&self, node_ids: &HashSet<i64>) -> Vec<i64> {
+        let mut ways: Vec<i64> = node_ids
+            .iter()
+            .filter_map(|node_id| self.uses.get(node_id))
+            .flat_map(|node_use| node_use.ways.iter().copied())
+            .collect();
+        ways.sort_unstable();
+        ways.dedup();
+        ways
+    }
+
+    fn insert_way(&mut self, id: i64, way: UsefulWay) {
+        for (i, node_id) in way.refs.iter().enumerate() {
+            let node_use = self.uses.entry(*node_id).or_default();
+            node_use.refs += 1;
+            if i == 0 || i + 1 == way.refs.len() {
+                node_use.ends += 1;
+            }
+            if !node_use.ways.contains(&id) {
+                node_use.ways.push(id);
+            }
+        }
+        self.ways.insert(id, way);
+    }
+
+    fn remove_way(&mut self, id: i64) {
+        let Some(way) = self.ways.remove(&id) else {
+            return;
+        };
+        for (i, node_id) in way.refs.iter().enumerate() {
+            if let Some(node_use) = self.uses.get_mut(node_id) {
+                node_use.refs -= 1;
+                if i == 0 || i + 1 == way.refs.len() {
+                    node_use.ends -= 1;
+                }
+                node_use.ways.retain(|way_id| *way_id != id);
+                if node_use.refs == 0 {
+                    self.uses.remove(node_id);
+                }
+            }
+        }
+    }
+
+    //like the reader: ends of ways, junctions and barriers in the middle of a single way
+    fn is_tower_node(&self, id: i64) -> bool {
+        match self.uses.get(&id) {
+            Some(node_use) if self.nodes.contains_key(&id) => node_use.refs >= 2 || node_use.ends > 0 || self.is_splitting_barrier(id),
+            _ => false,
+        }
+    }
+
+    fn is_splitting_barrier(&self, id: i64) -> bool {
+        let in_the_middle = self.uses.get(&id).is_some_and(|u| u.refs == 1 && u.ends == 0);
+        in_the_middle && self.nodes.get(&id).is_some_and(|n| n.barrier.is_some())
+    }
+
+    fn find_tower_node(&self, id: i64) -> Option<TowerNode<'_>> {
+        if !self.is_tower_node(id) {
+            return None;
+        }
+
+        let (index, copy) = *self.indices.get(&id)?;
+        let node = &self.nodes[&id];
+        let splitting = self.is_splitting_barrier(id);
+        Some(TowerNode {
+            index,
+            location: node.location,
+            barrier: copy.zip(node.barrier.as_ref().filter(|_| splitting)),
+            junction_barrier: node.barrier.as_ref().filter(|_| !splitting),
+        })
+    }
+
+    //(from, to) of the edges of the way
+    fn segments(&self, way: &UsefulWay) -> Vec<(usize, usize)> {
+        let (segments, _) = create_segments(way, |id| self.find_tower_node(id));
+        segments.into_iter().map(|(from, to, _, _)| (from, to)).collect()
+    }
+}
+
+//ways whose nodes are kept because a change of their tags can make them useful
+fn is_candidate(tags: &TagsMap) -> bool {
+    tags.has_key("highway") || tags.has_key("route")
+}
+
+fn to_tags_map(tags: &[(String, String)]) -> TagsMap<'_> {
+    convert_tags_to_map(tags.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+}
@@ -0,0 +1,242 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, ErrorKind};
+
+use bzip2::read::BzDecoder;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+//the nodes and ways of an osm xml file, relations are skipped
+pub enum XmlElement {
+    Node {
+        id: i64,
+        lat: f64,
+        lon: f64,
+        tags: Vec<(String, String)>,
+    },
+    Way {
+        id: i64,
+        refs: Vec<i64>,
+        tags: Vec<(String, String)>,
+    },
+}
+
+//the block of an osmChange file that an element is in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChangeAction {
+    Create,
+    Modify,
+    Delete,
+}
+
+pub fn is_osm_xml(file_name: &str) -> bool {
+    file_name.ends_with(".osm") || file_name.ends_with(".osm.bz2")
+}
+
+/// Calls f for every node and way of an .osm or .osm.bz2 file, in the order of the file
+/// objects that josm marks as deleted (action="delete") or that aren't visible anymore are skipped
+pub fn read_osm_xml(file_name: &str, mut f: impl FnMut(XmlElement)) -> Result<(), io::Error> {
+    read_file(file_name, |action, element| {
+        if action.is_none() {
+            f(element);
+        }
+    })
+}
+
+/// Calls f for every node and way inside the create, modify and delete blocks of an .osc or .osc.bz2 file (osmChange), in the order of the file
+/// the nodes of the delete block can leave out their coordinates, they are 0 then
+pub fn read_osm_change(file_name: &str, mut f: impl FnMut(ChangeAction, XmlElement)) -> Result<(), io::Error> {
+    read_file(file_name, |action, element| {
+        if let Some(action) = action {
+            f(action, element);
+        }
+    })
+}
+
+fn read_file(file_name: &str, f: impl FnMut(Option<ChangeAction>, XmlElement)) -> Result<(), io::Error> {
+    let file = File::open(file_name)?;
+    if file_name.ends_with(".bz2") {
+        read_elements(BufReader::new(BzDecoder::new(file)), f)
+    } else {
+        read_elements(BufReader::new(file), f)
+    }
+}
+
+//the action is None for elements outside of an osmChange block
+fn read_elements<R: BufRead>(reader: R, mut f: impl FnMut(Option<ChangeAction>, XmlElement)) -> Result<(), io::Error> {
+    let mut reader = Reader::from_reader(reader);
+
+    let mut buf = vec![];
+    let mut action = None;
+    let mut current: Option<XmlElement> = None; //the node or way whose tags and refs are being read
+
+    loop {
+        match reader.read_event_into(&mut buf).map_err(invalid_data)? {
+            Event::Start(e) => match e.name().as_ref() {
+                b"node" | b"way" => current = parse_element(&e, action)?,
+                b"relation" => current = None,
+                b"create" => action = Some(ChangeAction::Create),
+                b"modify" => action = Some(ChangeAction::Modify),
+                b"delete" => action = Some(ChangeAction::Delete),
+                _ => {}
+            },
+            Event::Empty(e) => match (e.name().as_ref(), current.as_mut()) {
+                (b"node" | b"way", _) => {
+                    if let Some(element) = parse_element(&e, action)? {
+                        f(action, element);
+                    }
+                }
+                (b"tag", Some(XmlElement::Node { tags, .. } | XmlElement::Way { tags, .. })) => {
+                    let key = get_attribute(&e, b"k")?.ok_or_else(|| invalid_data_message("tag without k"))?;
+                    let value = get_attribute(&e, b"v")?.unwrap_or_default();
+                    tags.push((key, value));
+                }
+                (b"nd", Some(XmlElement::Way { refs, .. })) => {
+                    let node_id = get_attribute(&e, b"ref")?.and_then(|r| r.parse::<i64>().ok());
+                    refs.push(node_id.ok_or_else(|| invalid_data_message("nd without a valid ref"))?);
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.name().as_ref() {
+                b"node" | b"way" => {
+                    if let Some(element) = current.take() {
+                        f(action, element);
+                    }
+                }
+                b"create" | b"modify" | b"delete" => action = None,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(())
+}
+
+//None if the element is deleted, only outside of an osmChange block because deleted elements are part of a change
+fn parse_element(e: &BytesStart, action: Option<ChangeAction>) -> Result<Option<XmlElement>, io::Error> {
+    let deleted = get_attribute(e, b"action")?.as_deref() == Some("delete") || get_attribute(e, b"visible")?.as_deref() == Some("false");
+    if deleted && action.is_none() {
+        return Ok(None);
+    }
+
+    let id = get_number::<i64>(e, b"id")?;
+    let element = if e.name().as_ref() == b"node" {
+        let coordinate = |key: &[u8]| match action {
+            Some(ChangeAction::Delete) => Ok(get_number(e, key).unwrap_or(0.0)),
+            _ => get_number(e, key),
+        };
+        XmlElement::Node {
+            id,
+            lat: coordinate(b"lat")?,
+            lon: coordinate(b"lon")?,
+            tags: vec![],
+        }
+    } else {
+        XmlElement::Way {
+            id,
+            refs: vec![],
+            tags: vec![],
+        }
+    };
+
+    Ok(Some(element))
+}
+
+fn get_number<T: std::str::FromStr>(e: &BytesStart, key: &[u8]) -> Result<T, io::Error> {
+    get_attribute(e, key)?.and_then(|v| v.parse::<T>().ok()).ok_or_else(|| {
+        let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+        invalid_data_message(&format!("{} without a valid {}", name, String::from_utf8_lossy(key)))
+    })
+}
+
+fn get_attribute(e: &BytesStart, key: &[u8]) -> Result<Option<String>, io::Error> {
+    for attribute in e.attributes() {
+        let attribute = attribute.map_err(invalid_data)?;
+        if attribute.key.as_ref() == key {
+            return Ok(Some(attribute.unescape_value().map_err(invalid_data)?.into_owned()));
+        }
+    }
+
+    Ok(None)
+}
+
+fn invalid_data<E: std::error::Error + Send + Sync + 'static>(e: E) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, e)
+}
+
+fn invalid_data_message(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_elements() {
+        let xml = r#"<?xml version='1.0' encoding='UTF-8'?>
+<osm version='0.6' generator='JOSM'>
+  <node id='-1' action='modify' lat='51.0' lon='3.7' />
+  <node id='2' lat='51.001' lon='3.7'>
+    <tag k='barrier' v='gate' />
+  </node>
+  <node id='3' action='delete' lat='51.002' lon='3.7' />
+  <way id='10'>
+    <nd ref='-1' />
+    <nd ref='2' />
+    <tag k='highway' v='residential' />
+    <tag k='name' v='Rue &amp; Straat' />
+  </way>
+  <relation id='20'>
+    <member type='way' ref='10' role='' />
+    <tag k='type' v='route' />
+  </relation>
+</osm>"#;
+
+        let mut elements = vec![];
+        read_elements(xml.as_bytes(), |_, e| elements.push(e)).unwrap();
+        assert_eq!(elements.len(), 3);
+
+        assert!(matches!(&elements[0], XmlElement::Node { id: -1, tags, .. } if tags.is_empty()));
+        assert!(matches!(&elements[1], XmlElement::Node { id: 2, tags, .. } if tags == &[("barrier".to_string(), "gate".to_string())]));
+        match &elements[2] {
+            XmlElement::Way { id, refs, tags } => {
+                assert_eq!(*id, 10);
+                assert_eq!(refs, &[-1, 2]);
+                assert_eq!(tags[1].1, "Rue & Straat");
+            }
+            _ => panic!("expected a way"),
+        }
+
+        assert!(read_elements("<osm><node id='1' lat='x' lon='3.7' /></osm>".as_bytes(), |_, _| {}).is_err());
+    }
+
+    #[test]
+    fn test_read_change() {
+        let xml = r#"<?xml version='1.0' encoding='UTF-8'?>
+<osmChange version='0.6'>
+  <create>
+    <node id='5' version='1' lat='51.0' lon='3.7' />
+  </create>
+  <modify>
+    <way id='10' version='2'>
+      <nd ref='1' />
+      <nd ref='5' />
+    </way>
+  </modify>
+  <delete>
+    <node id='3' version='3' visible='false' />
+  </delete>
+</osmChange>"#;
+
+        let mut elements = vec![];
+        read_elements(xml.as_bytes(), |action, e| elements.push((action, e))).unwrap();
+        assert_eq!(elements.len(), 3);
+
+        assert!(matches!(&elements[0], (Some(ChangeAction::Create), XmlElement::Node { id: 5, .. })));
+        assert!(matches!(&elements[1], (Some(ChangeAction::Modify), XmlElement::Way { id: 10, refs, .. }) if refs == &[1, 5]));
+        assert!(matches!(&elements[2], (Some(ChangeAction::Delete), XmlElement::Node { id: 3, lat, .. }) if *lat == 0.0));
+    }
+}
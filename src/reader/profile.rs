@@ -0,0 +1,262 @@
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, ErrorKind};
+
+use crate::core::edge::VehicleTypes;
+
+//which roads a vehicle type can use and how fast it drives on them
+//the reader creates the edge information of every profile in the same pass
+#[derive(Deserialize, Debug, Clone)]
+pub struct Profile {
+    pub name: String,
+    pub vehicle_type: VehicleTypes,
+
+    //highway classes that can be used without an explicit access tag, with their speed in km/h
+    pub highways: HashMap<String, f64>,
+
+    //from most to least specific, the first key that is present on a way decides the access
+    #[serde(default = "default_access_keys")]
+    pub access_keys: Vec<String>,
+    //values of the access keys that allow access
+    #[serde(default = "default_access_values")]
+    pub access_values: HashSet<String>,
+
+    //keys with values like yes or -1
+    #[serde(default = "default_oneway_keys")]
+    pub oneway_keys: Vec<String>,
+
+    //speed in km/h for roads that are not in highways, but are allowed because of an explicit access tag
+    #[serde(default = "default_speed")]
+    pub default_speed: f64,
+
+    //speed in km/h of ferries and shuttle trains without a duration tag
+    #[serde(default = "default_ferry_speed")]
+    pub ferry_speed: f64,
+
+    //the speed is multiplied with the factor of the surface of the way
+    #[serde(default)]
+    pub surface_factors: HashMap<String, f64>,
+
+    //values of barrier that the vehicle can't pass, unless an access tag on the node allows it
+    #[serde(default)]
+    pub blocked_barriers: HashSet<String>,
+}
+
+#[derive(Deserialize)]
+struct ProfilesFile {
+    profiles: Vec<Profile>,
+}
+
+fn default_access_keys() -> Vec<String> {
+    vec!["access".to_string()]
+}
+
+fn default_access_values() -> HashSet<String> {
+    to_strings(&["yes", "permissive", "designated", "open", "destination", "delivery"])
+}
+
+fn default_oneway_keys() -> Vec<String> {
+    vec!["oneway".to_string()]
+}
+
+fn default_speed() -> f64 {
+    10.0
+}
+
+fn default_ferry_speed() -> f64 {
+    25.0
+}
+
+fn to_strings<C: FromIterator<String>>(values: &[&str]) -> C {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+impl Profile {
+    //the profile that used to be hardcoded in vehicle_permissions
+    pub fn car() -> Self {
+        //Where we can accept cars by default, without an explicit car tag
+        //so if theres no explicit car tag on a road thats not in here then reject
+        let highways = [
+            ("motorway", 100.0),
+            ("motorway_link", 70.0),
+            ("motorroad", 90.0),
+            ("trunk", 70.0),
+            ("trunk_link", 65.0),
+            ("primary", 65.0),
+            ("primary_link", 60.0),
+            ("secondary", 60.0),
+            ("secondary_link", 50.0),
+            ("tertiary", 50.0),
+            ("tertiary_link", 40.0),
+            ("unclassified", 30.0),
+            ("residential", 30.0),
+            ("living_street", 5.0),
+            ("service", 20.0),
+            ("road", 20.0),
+        ];
+
+        Profile {
+            name: "car".to_string(),
+            vehicle_type: VehicleTypes::Car,
+            highways: highways.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            access_keys: to_strings(&["motorcar", "motor_vehicle", "vehicle", "access"]),
+            access_values: default_access_values(),
+            oneway_keys: to_strings(&["oneway", "oneway:vehicle", "oneway:motor_vehicle"]),
+            default_speed: default_speed(),
+            ferry_speed: default_ferry_speed(),
+            surface_factors: motor_vehicle_surface_factors(),
+            blocked_barriers: motor_vehicle_blocked_barriers(),
+        }
+    }
+
+    //trucks use the same roads as cars, but slower. Their weight and size limits are checked per request, see VehicleDimensions
+    pub fn hgv() -> Self {
+        let highways = [
+            ("motorway", 80.0),
+            ("motorway_link", 60.0),
+            ("motorroad", 80.0),
+            ("trunk", 70.0),
+            ("trunk_link", 55.0),
+            ("primary", 60.0),
+            ("primary_link", 50.0),
+            ("secondary", 55.0),
+            ("secondary_link", 45.0),
+            ("tertiary", 45.0),
+            ("tertiary_link", 35.0),
+            ("unclassified", 25.0),
+            ("residential", 25.0),
+            ("living_street", 5.0),
+            ("service", 15.0),
+            ("road", 15.0),
+        ];
+
+        Profile {
+            name: "hgv".to_string(),
+            vehicle_type: VehicleTypes::Hgv,
+            highways: highways.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            access_keys: to_strings(&["hgv", "motor_vehicle", "vehicle", "access"]),
+            access_values: default_access_values(),
+            oneway_keys: to_strings(&["oneway", "oneway:vehicle", "oneway:motor_vehicle"]),
+            default_speed: default_speed(),
+            ferry_speed: default_ferry_speed(),
+            surface_factors: motor_vehicle_surface_factors(),
+            blocked_barriers: motor_vehicle_blocked_barriers(),
+        }
+    }
+}
+
+fn motor_vehicle_blocked_barriers() -> HashSet<String> {
+    to_strings(&[
+        "bollard",
+        "block",
+        "bus_trap",
+        "cycle_barrier",
+        "jersey_barrier",
+        "kissing_gate",
+        "stile",
+        "turnstile",
+        "full-height_turnstile",
+    ])
+}
+
+fn motor_vehicle_surface_factors() -> HashMap<String, f64> {
+    let surface_factors = [
+        ("unpaved", 0.6),
+        ("compacted", 0.8),
+        ("gravel", 0.6),
+        ("fine_gravel", 0.6),
+        ("dirt", 0.5),
+        ("ground", 0.5),
+        ("grass", 0.4),
+        ("sand", 0.4),
+        ("cobblestone", 0.7),
+        ("sett", 0.7),
+    ];
+
+    surface_factors.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+}
+
+/// Read the profiles from a json file (.json) or a toml file (any other extension)
+/// both contain a list called profiles, every vehicle type can only be used by one profile
+pub fn read_profiles(file_name: &str) -> Result<Vec<Profile>, io::Error> {
+    let text = fs::read_to_string(file_name)?;
+    if file_name.ends_with(".json") {
+        profiles_from_json(&text)
+    } else {
+        profiles_from_toml(&text)
+    }
+}
+
+pub fn profiles_from_toml(text: &str) -> Result<Vec<Profile>, io::Error> {
+    let file: ProfilesFile = toml::from_str(text).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+    validate(file.profiles)
+}
+
+pub fn profiles_from_json(text: &str) -> Result<Vec<Profile>, io::Error> {
+    let file: ProfilesFile = serde_json::from_str(text).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+    validate(file.profiles)
+}
+
+fn validate(profiles: Vec<Profile>) -> Result<Vec<Profile>, io::Error> {
+    let mut vehicle_types = HashSet::new();
+    for profile in profiles.iter() {
+        if !vehicle_types.insert(profile.vehicle_type) {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("vehicle type {:?} of profile {} is used by another profile", profile.vehicle_type, profile.name),
+            ));
+        }
+
+        let positive = |v: f64| v > 0.0; //also false for nan
+        let mut values = profile.highways.values().chain(profile.surface_factors.values());
+        if !positive(profile.default_speed) || !positive(profile.ferry_speed) || values.any(|v| !positive(*v)) {
+            return Err(io::Error::new(ErrorKind::InvalidData, format!("speeds and surface factors of profile {} must be positive", profile.name)));
+        }
+    }
+
+    Ok(profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_from_toml() {
+        let profiles = profiles_from_toml(
+            r#"
+            [[profiles]]
+            name = "bike"
+            vehicle_type = "bike"
+            highways = { cycleway = 18.0, residential = 15.0 }
+            access_keys = ["bicycle", "vehicle", "access"]
+            surface_factors = { gravel = 0.5 }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].vehicle_type, VehicleTypes::Bike);
+        assert_eq!(profiles[0].highways.get("cycleway"), Some(&18.0));
+        assert_eq!(profiles[0].oneway_keys, vec!["oneway".to_string()]);
+        assert!(profiles[0].access_values.contains("designated"));
+    }
+
+    #[test]
+    fn test_profiles_from_json() {
+        let profiles = profiles_from_json(r#"{"profiles": [{"name": "car", "vehicle_type": "car", "highways": {"primary": 80}}]}"#).unwrap();
+        assert_eq!(profiles[0].vehicle_type, VehicleTypes::Car);
+        assert_eq!(profiles[0].default_speed, 10.0);
+        assert_eq!(profiles[0].ferry_speed, 25.0);
+
+        //the same vehicle type twice
+        let result = profiles_from_json(
+            r#"{"profiles": [{"name": "a", "vehicle_type": "car", "highways": {}}, {"name": "b", "vehicle_type": "car", "highways": {}}]}"#,
+        );
+        assert!(result.is_err());
+
+        let result = profiles_from_json(r#"{"profiles": [{"name": "a", "vehicle_type": "car", "highways": {"primary": -1}}]}"#);
+        assert!(result.is_err());
+    }
+}
@@ -1,9 +1,11 @@
-use osmpbf::TagIter;
+use std::borrow::Borrow;
 use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 
 pub struct TagsMap<'a>(pub HashMap<&'a str, &'a str>);
 
-pub fn convert_tags_to_map(tags: TagIter) -> TagsMap {
+//works for the tags of ways and of (dense) nodes
+pub fn convert_tags_to_map<'a>(tags: impl Iterator<Item = (&'a str, &'a str)>) -> TagsMap<'a> {
     let mut map = HashMap::new();
     for tag in tags {
         map.insert(tag.0, tag.1);
@@ -21,9 +23,9 @@ impl<'a> TagsMap<'a> {
         false
     }
 
-    pub fn tag_in_values(&self, key: &str, values: &HashSet<&str>) -> bool {
+    pub fn tag_in_values<V: Borrow<str> + Eq + Hash>(&self, key: &str, values: &HashSet<V>) -> bool {
         if let Some(tag_value) = self.0.get(key) {
-            return values.contains(tag_value);
+            return values.contains(*tag_value);
         }
 
         false
@@ -31,16 +33,16 @@ impl<'a> TagsMap<'a> {
 
     pub fn has_key(&self, key: &str) -> bool {
         //doesn't matter which value
-        self.0.get(key).is_some()
+        self.0.contains_key(key)
     }
 
     //returns if one of the keys has a value in values (in the right order)
     //return false if theres one key that does have a value but the value is not inside values
     //second bool is to indicate if there was an explicit tag or not
-    pub fn has_tag_ordered(&self, keys: &[&str], values: &HashSet<&str>) -> (bool, bool) {
+    pub fn has_tag_ordered<K: AsRef<str>, V: Borrow<str> + Eq + Hash>(&self, keys: &[K], values: &HashSet<V>) -> (bool, bool) {
         for key in keys.iter() {
-            if let Some(tag_value) = self.0.get(key) {
-                return (values.contains(tag_value), true);
+            if let Some(tag_value) = self.0.get(key.as_ref()) {
+                return (values.contains(*tag_value), true);
             } //else its None and we can try the next key value
         }
 
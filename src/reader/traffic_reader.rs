@@ -0,0 +1,77 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, ErrorKind};
+use std::rc::Rc;
+
+use crate::core::traffic::{SpeedProfile, SpeedUpdate, TrafficKey};
+
+//reads a csv feed of speed updates, with lines:
+//way,<way id>,<speed in km/h>,<expires at>
+//edge,<osm node id>,<osm node id>,<speed in km/h>,<expires at>
+//expires at is in seconds since the unix epoch and can be empty. The first line can be the header type,id,speed,expires_at
+pub fn read_speed_updates(file_name: &str) -> Result<Vec<SpeedUpdate>, io::Error> {
+    let reader = BufReader::new(File::open(file_name)?);
+
+    let mut updates = vec![];
+    for (line_nr, line) in reader.lines().enumerate() {
+        let line = line?;
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        if line.trim().is_empty() || (line_nr == 0 && fields == ["type", "id", "speed", "expires_at"]) {
+            continue;
+        }
+
+        let invalid = |message: &str| io::Error::new(ErrorKind::InvalidData, format!("{} on line {}", message, line_nr + 1));
+        let parse = |field: &str| field.parse::<f64>().map_err(|_| invalid(&format!("invalid number {}", field)));
+        let parse_id = |field: &str| field.parse::<i64>().map_err(|_| invalid(&format!("invalid id {}", field)));
+
+        let (key, rest) = match (fields[0], fields.len()) {
+            ("way", 4) => (TrafficKey::Way(parse_id(fields[1])?), &fields[2..]),
+            ("edge", 5) => (TrafficKey::Edge(parse_id(fields[1])?, parse_id(fields[2])?), &fields[3..]),
+            _ => return Err(invalid("expected a way with 4 fields or an edge with 5 fields")),
+        };
+
+        updates.push(SpeedUpdate {
+            key,
+            speed: parse(rest[0])?,
+            expires_at: if rest[1].is_empty() { None } else { Some(parse(rest[1])?) },
+        });
+    }
+
+    Ok(updates)
+}
+
+//reads a csv file of speed profiles, with lines:
+//way,<way id>,<speed 1>,...,<speed n>
+//edge,<osm node id>,<osm node id>,<speed 1>,...,<speed n>
+//with 96 (every day) or 672 (monday to sunday) speeds in km/h per 15 minutes, starting at 00:00 utc. The first line can be the header type,id,speeds
+//the edges are looked up when the profiles are added to a graph, see SpeedProfiles::add
+pub fn read_speed_profiles(file_name: &str) -> Result<Vec<(TrafficKey, Rc<SpeedProfile>)>, io::Error> {
+    let reader = BufReader::new(File::open(file_name)?);
+
+    let mut profiles = vec![];
+    for (line_nr, line) in reader.lines().enumerate() {
+        let line = line?;
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        if line.trim().is_empty() || (line_nr == 0 && fields == ["type", "id", "speeds"]) {
+            continue;
+        }
+
+        let invalid = |message: &str| io::Error::new(ErrorKind::InvalidData, format!("{} on line {}", message, line_nr + 1));
+        let parse_id = |field: &str| field.parse::<i64>().map_err(|_| invalid(&format!("invalid id {}", field)));
+
+        let (key, speeds) = match fields[0] {
+            "way" if fields.len() > 1 => (TrafficKey::Way(parse_id(fields[1])?), &fields[2..]),
+            "edge" if fields.len() > 2 => (TrafficKey::Edge(parse_id(fields[1])?, parse_id(fields[2])?), &fields[3..]),
+            _ => return Err(invalid("expected a way or an edge")),
+        };
+
+        let speeds = speeds
+            .iter()
+            .map(|s| s.parse::<f64>().map_err(|_| invalid(&format!("invalid number {}", s))))
+            .collect::<Result<Vec<f64>, io::Error>>()?;
+        let profile = SpeedProfile::new(speeds).ok_or_else(|| invalid("expected 96 or 672 speeds that aren't negative"))?;
+
+        profiles.push((key, Rc::new(profile)));
+    }
+
+    Ok(profiles)
+}
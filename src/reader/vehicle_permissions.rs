@@ -1,28 +1,72 @@
 use lazy_static::lazy_static;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use super::profile::Profile;
 use super::tags_map::TagsMap;
+use crate::core::conditional::{parse_conditional_value, ConditionalRestriction};
+use crate::core::edge::PhysicalRestrictions;
 
 lazy_static! (
-    static ref DEFAULT_VALUES: HashSet<&'static str> = HashSet::from_iter(["yes", "permissive", "designated", "open", "destination", "delivery"].iter().cloned());
+    //the rules for cars that used to be hardcoded here
+    static ref CAR_PROFILE: Profile = Profile::car();
+
     static ref ONEWAY_VALUES: HashSet<&'static str> = HashSet::from_iter(["yes","true","1","-1","reverse"]);
     static ref ONEWAY_FORWARD_VALUES: HashSet<&'static str> = HashSet::from_iter(["yes","true","1"]);
     static ref ONEWAY_BACKWARD_VALUES: HashSet<&'static str> = HashSet::from_iter(["-1","reverse"]);
 
-    //Where we can accept cars by default, without an explicit car tag
-    //so if theres no explicit car tag on a road thats not in here then reject
-    static ref NORMAL_ROADS: HashSet<&'static str> = HashSet::from_iter(["motorway","motorway_link","motorroad", "trunk", "trunk_link", "primary", "primary_link","secondary","secondary_link","tertiary","tertiary_link","unclassified","residential","living_street","service","road"]);
     static ref SPECIAL_ROADS: HashSet<&'static str> = HashSet::from_iter(["pedestrian", "footway", "path", "bridleway", "cycleway", "steps", "platform", "bus_stop", "busway", "bus_guideway", "emergency_access_point", "no", "proposed", "construction", "abandoned"]);
 
     static ref FERRY_ROADS: HashSet<&'static str> = HashSet::from_iter(["shuttle_train","ferry"]);
+
+    //seconds needed to pass a barrier, for every vehicle that can pass it
+    static ref BARRIER_PENALTIES: HashMap<&'static str, f64> = HashMap::from_iter([("gate", 15.0), ("lift_gate", 10.0), ("toll_booth", 30.0), ("border_control", 60.0)]);
 );
 
 //returns if a car is allowed to drive on the given road (forward direction and backward direction)
 pub fn is_car_allowed(tags: &TagsMap) -> (bool, bool) {
-    if !tags.has_key("highway") {
+    is_allowed(tags, &CAR_PROFILE)
+}
+
+//returns if the vehicle of the profile is allowed to drive on the given road (forward direction and backward direction)
+pub fn is_allowed(tags: &TagsMap, profile: &Profile) -> (bool, bool) {
+    if is_ferry(tags) {
+        //ferries and shuttle trains only carry the vehicle when a tag like motor_vehicle=yes says so
+        let (allowed, explicit) = tags.has_tag_ordered(&profile.access_keys, &profile.access_values);
+        if !allowed || !explicit {
+            return (false, false);
+        }
+
+        return get_oneway(tags, profile);
+    }
+
+    if !is_road(tags) {
         return (false, false);
     }
 
+    let (allowed, explicit) = tags.has_tag_ordered(&profile.access_keys, &profile.access_values);
+    if !allowed && explicit {
+        // this must mean theres an explicit tag saying this vehicle isn't allowed
+        return (false, false);
+    } else if !explicit {
+        //there is no explicit tag saying anything about access for this vehicle
+        if !tags.0.get("highway").is_some_and(|h| profile.highways.contains_key(*h)) {
+            //SPECIAL_ROADS and normal roads are disjunct this is good enough
+            return (false, false);
+        }
+    }
+
+    //now we can assume that either there is an explicit car tag
+    //or there is no explicit car tag but there are also no explicit foot and bike tags
+    //so this is assumed to be a car way. We still have to check if it is a oneway or not
+    get_oneway(tags, profile)
+}
+
+//if the way is a road that any vehicle could use
+fn is_road(tags: &TagsMap) -> bool {
+    if !tags.has_key("highway") {
+        return false;
+    }
+
     if tags.tag_equals("area", "yes")
         || tags.has_key("parking")
         || tags.has_key("amenity")
@@ -38,52 +82,207 @@ pub fn is_car_allowed(tags: &TagsMap) -> (bool, bool) {
         || tags.has_key("waterway")
         || tags.has_key("boundary")
     {
-        return (false, false);
+        return false;
     }
 
-    if tags.tag_in_values("road", &FERRY_ROADS) {
-        return (false, false);
+    !(tags.tag_equals("impassable", "yes") || tags.tag_equals("status", "impassable"))
+}
+
+pub fn is_ferry(tags: &TagsMap) -> bool {
+    tags.tag_in_values("route", &FERRY_ROADS)
+}
+
+//the directions the oneway tags of the profile allow (forward direction and backward direction)
+fn get_oneway(tags: &TagsMap, profile: &Profile) -> (bool, bool) {
+    let mut forward = true;
+    let mut backward = true;
+    if profile.oneway_keys.iter().any(|key| tags.tag_in_values(key, &ONEWAY_FORWARD_VALUES)) {
+        backward = false;
+    } else if profile.oneway_keys.iter().any(|key| tags.tag_in_values(key, &ONEWAY_BACKWARD_VALUES)) {
+        forward = false;
     }
 
-    if tags.tag_equals("impassable", "yes") || tags.tag_equals("status", "impassable") {
-        return (false, false);
+    (forward, backward)
+}
+
+/// The :conditional variants of the access and oneway keys of the profile, like motor_vehicle:conditional=no @ (Mo-Fr 07:00-19:00)
+/// like the plain access keys, the first access key with a conditional variant decides. Conditions that aren't times are ignored
+pub fn get_conditional_restrictions(tags: &TagsMap, profile: &Profile) -> Vec<ConditionalRestriction> {
+    if !is_road(tags) {
+        return vec![];
     }
 
-    let (car, explicit) = tags.has_tag_ordered(&["motorcar", "motor_vehicle", "vehicle", "access"], &DEFAULT_VALUES);
-    if !car && explicit {
-        // this must mean theres an explicit tag saying cars aren't allowed
-        return (false, false);
-    } else if !explicit {
-        //there is no explicit tag saying anything about car access
-        if !tags.tag_in_values("highway", &NORMAL_ROADS) {
-            //SPECIAL_ROADS and normal roads are disjunct this is good enough
-            return (false, false);
+    let mut restrictions = vec![];
+
+    //access is only granted in the directions that the oneway tags allow
+    let (forward, backward) = get_oneway(tags, profile);
+    let access_value = profile
+        .access_keys
+        .iter()
+        .find_map(|key| tags.0.get(format!("{}:conditional", key).as_str()));
+    for (value, hours) in access_value.map(|v| parse_conditional_value(v)).unwrap_or_default() {
+        let allowed = profile.access_values.contains(value);
+        restrictions.push(ConditionalRestriction {
+            forward: if allowed { forward.then_some(true) } else { Some(false) },
+            backward: if allowed { backward.then_some(true) } else { Some(false) },
+            hours,
+        });
+    }
+
+    for key in profile.oneway_keys.iter() {
+        for (value, hours) in tags
+            .0
+            .get(format!("{}:conditional", key).as_str())
+            .map(|v| parse_conditional_value(v))
+            .unwrap_or_default()
+        {
+            let (forward, backward) = if ONEWAY_FORWARD_VALUES.contains(value) {
+                (None, Some(false))
+            } else if ONEWAY_BACKWARD_VALUES.contains(value) {
+                (Some(false), None)
+            } else {
+                continue;
+            };
+            restrictions.push(ConditionalRestriction { forward, backward, hours });
         }
     }
 
-    //now we can assume that either there is an explicit car tag
-    //or there is no explicit car tag but there are also no explicit foot and bike tags
-    //so this is assumed to be a car way. We still have to check if it is a oneway or not
+    restrictions
+}
 
-    let mut forward = true;
-    let mut backward = true;
-    if tags.tag_in_values("oneway", &ONEWAY_FORWARD_VALUES)
-        || tags.tag_in_values("oneway:vehicle", &ONEWAY_FORWARD_VALUES)
-        || tags.tag_in_values("oneway:motor_vehicle", &ONEWAY_FORWARD_VALUES)
-    {
-        backward = false;
-    } else if tags.tag_in_values("oneway", &ONEWAY_BACKWARD_VALUES)
-        || tags.tag_in_values("oneway:vehicle", &ONEWAY_BACKWARD_VALUES)
-        || tags.tag_in_values("oneway:motor_vehicle", &ONEWAY_BACKWARD_VALUES)
-    {
-        forward = false;
+//the legal weight and size limits of the road, vehicle specific keys like maxweight:hgv have priority
+pub fn get_physical_restrictions(tags: &TagsMap) -> PhysicalRestrictions {
+    let limit = |key: &str, parse: fn(&str) -> Option<f64>| {
+        tags.0
+            .get(format!("{}:hgv", key).as_str())
+            .or_else(|| tags.0.get(key))
+            .and_then(|v| parse(v))
+    };
+
+    PhysicalRestrictions {
+        max_weight: limit("maxweight", parse_weight),
+        max_axle_load: limit("maxaxleload", parse_weight),
+        max_height: limit("maxheight", parse_length),
+        max_width: limit("maxwidth", parse_length),
+        max_length: limit("maxlength", parse_length),
+        no_hazmat: tags.tag_equals("hazmat", "no"),
     }
+}
 
-    (forward, backward)
+//in tonnes: "7.5", "7.5 t", "7500 kg" or "16000 lbs". Values like none or default are no limit
+fn parse_weight(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let number_end = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let number = value[..number_end].parse::<f64>().ok()?;
+
+    let factor = match value[number_end..].trim() {
+        "" | "t" => 1.0,
+        "kg" => 0.001,
+        "lbs" => 0.000_453_592_37,
+        _ => return None,
+    };
+
+    Some(number * factor)
+}
+
+//in metres: "3.5", "3.5 m", "350 cm", "12'6\"" or "12 ft". Values like none or default are no limit
+fn parse_length(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if let Some((feet, inches)) = value.split_once('\'') {
+        let inches = inches.trim().trim_end_matches('"');
+        let inches = if inches.is_empty() { 0.0 } else { inches.parse::<f64>().ok()? };
+        return Some((feet.trim().parse::<f64>().ok()? * 12.0 + inches) * 0.0254);
+    }
+
+    let number_end = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let number = value[..number_end].parse::<f64>().ok()?;
+
+    let factor = match value[number_end..].trim() {
+        "" | "m" => 1.0,
+        "cm" => 0.01,
+        "ft" => 0.3048,
+        _ => return None,
+    };
+
+    Some(number * factor)
+}
+
+//if the vehicle of the profile can pass the barrier of a node, an access tag on the node has priority over the kind of barrier
+pub fn is_barrier_passable(tags: &TagsMap, profile: &Profile) -> bool {
+    let (allowed, explicit) = tags.has_tag_ordered(&profile.access_keys, &profile.access_values);
+    if explicit {
+        return allowed;
+    }
+
+    !tags.0.get("barrier").is_some_and(|b| profile.blocked_barriers.contains(*b))
+}
+
+//seconds needed to pass the barrier of a node
+pub fn get_barrier_penalty(tags: &TagsMap) -> f64 {
+    tags.0.get("barrier").and_then(|b| BARRIER_PENALTIES.get(*b)).copied().unwrap_or(0.0)
+}
+
+//the duration tag of ferries in seconds: "mm", "hh:mm", "hh:mm:ss" or iso 8601 like "PT1H30M"
+pub fn parse_duration(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let seconds = if let Some(iso) = value.strip_prefix("PT") {
+        let mut seconds = 0.0;
+        let mut number = String::new();
+        for c in iso.chars() {
+            let factor = match c {
+                'H' => 3600.0,
+                'M' => 60.0,
+                'S' => 1.0,
+                _ => {
+                    number.push(c);
+                    continue;
+                }
+            };
+            seconds += number.parse::<f64>().ok()? * factor;
+            number.clear();
+        }
+        if !number.is_empty() {
+            return None;
+        }
+        seconds
+    } else {
+        let parts = value.split(':').map(|p| p.parse::<f64>().ok()).collect::<Option<Vec<f64>>>()?;
+        match parts[..] {
+            [minutes] => minutes * 60.0,
+            [hours, minutes] => hours * 3600.0 + minutes * 60.0,
+            [hours, minutes, seconds] => hours * 3600.0 + minutes * 60.0 + seconds,
+            _ => return None,
+        }
+    };
+
+    (seconds > 0.0).then_some(seconds)
+}
+
+//the speed in km/h of the vehicle of the profile on the given road, the road is expected to be allowed
+pub fn get_speed(tags: &TagsMap, profile: &Profile) -> f64 {
+    if is_ferry(tags) {
+        return profile.ferry_speed;
+    }
+
+    let speed = tags
+        .0
+        .get("highway")
+        .and_then(|h| profile.highways.get(*h))
+        .copied()
+        .unwrap_or(profile.default_speed);
+    let factor = tags
+        .0
+        .get("surface")
+        .and_then(|s| profile.surface_factors.get(*s))
+        .copied()
+        .unwrap_or(1.0);
+
+    speed * factor
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::profile::profiles_from_toml;
     use super::super::tags_map::TagsMap;
     use super::*;
     use std::collections::HashMap;
@@ -129,4 +328,204 @@ mod tests {
         let (fwd, bwd) = is_car_allowed(&tags_map);
         assert!(fwd && !bwd);
     }
+
+    #[test]
+    fn test_conditional_restrictions() {
+        //school street
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("highway", "residential");
+        map.insert("motor_vehicle:conditional", "no @ (Mo-Fr 07:45-08:30,15:00-15:45)");
+        let tags_map = TagsMap(map);
+        assert!(is_car_allowed(&tags_map) == (true, true));
+        let restrictions = get_conditional_restrictions(&tags_map, &CAR_PROFILE);
+        assert_eq!(restrictions.len(), 1);
+        assert!(restrictions[0].forward == Some(false) && restrictions[0].backward == Some(false));
+
+        //pedestrian zone with delivery hours, only in the direction of the oneway
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("highway", "pedestrian");
+        map.insert("oneway", "yes");
+        map.insert("motor_vehicle:conditional", "delivery @ (05:00-11:00); no @ (weight > 7.5)");
+        let tags_map = TagsMap(map);
+        assert!(is_car_allowed(&tags_map) == (false, false));
+        let restrictions = get_conditional_restrictions(&tags_map, &CAR_PROFILE);
+        assert_eq!(restrictions.len(), 1);
+        assert!(restrictions[0].grants_access());
+        assert!(restrictions[0].forward == Some(true) && restrictions[0].backward.is_none());
+
+        //the oneway only holds at night
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("highway", "secondary");
+        map.insert("oneway:conditional", "-1 @ (22:00-06:00)");
+        let tags_map = TagsMap(map);
+        let restrictions = get_conditional_restrictions(&tags_map, &CAR_PROFILE);
+        assert!(restrictions[0].forward == Some(false) && restrictions[0].backward.is_none());
+
+        //not a road
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("building", "yes");
+        map.insert("access:conditional", "yes @ (Mo-Fr 08:00-18:00)");
+        let tags_map = TagsMap(map);
+        assert!(get_conditional_restrictions(&tags_map, &CAR_PROFILE).is_empty());
+    }
+
+    #[test]
+    fn test_physical_restrictions() {
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("highway", "secondary");
+        map.insert("maxweight", "7.5");
+        map.insert("maxweight:hgv", "12000 kg");
+        map.insert("maxheight", "12'6\"");
+        map.insert("maxwidth", "2.5 m");
+        map.insert("maxlength", "none");
+        map.insert("maxaxleload", "10 t");
+        map.insert("hazmat", "no");
+        let restrictions = get_physical_restrictions(&TagsMap(map));
+
+        assert_eq!(restrictions.max_weight, Some(12.0));
+        assert!((restrictions.max_height.unwrap() - 3.81).abs() < 1E-9);
+        assert_eq!(restrictions.max_width, Some(2.5));
+        assert_eq!(restrictions.max_length, None);
+        assert_eq!(restrictions.max_axle_load, Some(10.0));
+        assert!(restrictions.no_hazmat);
+
+        assert_eq!(parse_length("350 cm"), Some(3.5));
+        assert_eq!(parse_length("default"), None);
+        assert_eq!(parse_weight("3.5 st"), None);
+
+        //hgv=no only excludes the hgv profile
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("highway", "residential");
+        map.insert("hgv", "no");
+        let tags_map = TagsMap(map);
+        assert!(is_allowed(&tags_map, &Profile::hgv()) == (false, false));
+        assert!(is_car_allowed(&tags_map) == (true, true));
+    }
+
+    #[test]
+    fn test_barriers() {
+        let bike_profile = profiles_from_toml(
+            r#"
+            [[profiles]]
+            name = "bike"
+            vehicle_type = "bike"
+            highways = { cycleway = 18.0 }
+            access_keys = ["bicycle", "vehicle", "access"]
+            blocked_barriers = ["stile"]
+            "#,
+        )
+        .unwrap()
+        .remove(0);
+
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("barrier", "bollard");
+        let tags_map = TagsMap(map);
+        assert!(!is_barrier_passable(&tags_map, &CAR_PROFILE));
+        assert!(is_barrier_passable(&tags_map, &bike_profile));
+        assert_eq!(get_barrier_penalty(&tags_map), 0.0);
+
+        //a gate that is closed for everyone except bikes
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("barrier", "gate");
+        map.insert("access", "private");
+        map.insert("bicycle", "yes");
+        let tags_map = TagsMap(map);
+        assert!(!is_barrier_passable(&tags_map, &CAR_PROFILE));
+        assert!(is_barrier_passable(&tags_map, &bike_profile));
+
+        //a bollard that can be lowered for cars
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("barrier", "bollard");
+        map.insert("motor_vehicle", "yes");
+        let tags_map = TagsMap(map);
+        assert!(is_barrier_passable(&tags_map, &CAR_PROFILE));
+
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("barrier", "toll_booth");
+        let tags_map = TagsMap(map);
+        assert!(is_barrier_passable(&tags_map, &CAR_PROFILE));
+        assert_eq!(get_barrier_penalty(&tags_map), 30.0);
+    }
+
+    #[test]
+    fn test_ferry() {
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("route", "ferry");
+        map.insert("motor_vehicle", "yes");
+        map.insert("duration", "01:30");
+        let tags_map = TagsMap(map);
+        assert!(is_car_allowed(&tags_map) == (true, true));
+        assert_eq!(get_speed(&tags_map, &CAR_PROFILE), 25.0);
+
+        //foot ferries and ferries without explicit access don't carry cars
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("route", "ferry");
+        let tags_map = TagsMap(map);
+        assert!(is_car_allowed(&tags_map) == (false, false));
+
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("route", "shuttle_train");
+        map.insert("railway", "rail");
+        map.insert("motorcar", "yes");
+        map.insert("oneway", "yes");
+        let tags_map = TagsMap(map);
+        assert!(is_car_allowed(&tags_map) == (true, false));
+
+        assert_eq!(parse_duration("01:30"), Some(5400.0));
+        assert_eq!(parse_duration("45"), Some(2700.0));
+        assert_eq!(parse_duration("1:02:03"), Some(3723.0));
+        assert_eq!(parse_duration("PT1H30M"), Some(5400.0));
+        assert_eq!(parse_duration("PT1H30"), None);
+        assert_eq!(parse_duration("unknown"), None);
+    }
+
+    #[test]
+    fn test_profile() {
+        let profile = profiles_from_toml(
+            r#"
+            [[profiles]]
+            name = "bike"
+            vehicle_type = "bike"
+            highways = { cycleway = 18.0, residential = 15.0 }
+            access_keys = ["bicycle", "vehicle", "access"]
+            oneway_keys = ["oneway:bicycle"]
+            surface_factors = { gravel = 0.5 }
+            "#,
+        )
+        .unwrap()
+        .remove(0);
+
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("highway", "cycleway");
+        map.insert("surface", "gravel");
+        let tags_map = TagsMap(map);
+        let (fwd, bwd) = is_allowed(&tags_map, &profile);
+        assert!(fwd && bwd);
+        assert_eq!(get_speed(&tags_map, &profile), 9.0);
+        assert!(is_car_allowed(&tags_map) == (false, false));
+
+        //oneway only applies to the configured keys
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("highway", "residential");
+        map.insert("oneway", "yes");
+        let tags_map = TagsMap(map);
+        let (fwd, bwd) = is_allowed(&tags_map, &profile);
+        assert!(fwd && bwd);
+
+        //explicit access on a road that isn't allowed by default, with the default speed
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("highway", "footway");
+        map.insert("bicycle", "yes");
+        let tags_map = TagsMap(map);
+        let (fwd, bwd) = is_allowed(&tags_map, &profile);
+        assert!(fwd && bwd);
+        assert_eq!(get_speed(&tags_map, &profile), 10.0);
+
+        let mut map: HashMap<&str, &str> = HashMap::new();
+        map.insert("highway", "residential");
+        map.insert("bicycle", "no");
+        let tags_map = TagsMap(map);
+        let (fwd, bwd) = is_allowed(&tags_map, &profile);
+        assert!(!fwd && !bwd);
+    }
 }
@@ -1,25 +1,67 @@
-use kdtree::distance::squared_euclidean;
-use kdtree::KdTree;
-use perionav::core::components::options::AlgorithmType as ComponentsAlgorithmType;
-use perionav::core::components::options::ComponentsAlgorithmOptions;
-use perionav::core::routing::options::{AlgorithmType, RoutingAlgorithmOptions, WeightType};
-use perionav::core::routing::Path;
-use perionav::core::routing::RoutingResult;
-use perionav::core::Graph;
-use perionav::reader::osm_reader::OsmReader;
-use std::collections::HashSet;
+use crate::core::avoidareas::{create_avoid_areas_weight_calculator, Area};
+use crate::core::components::filter::{get_kept_nodes, ComponentsFilter};
+use crate::core::components::options::AlgorithmType as ComponentsAlgorithmType;
+use crate::core::components::vehicle::{get_vehicle_components, VehicleNeighbors};
+use crate::core::edge::VehicleTypes;
+use crate::core::matching::{self, GpsPoint, MapMatchingOptions, MapMatchingResult};
+use crate::core::routing::dijkstra::DijkstraRoutingAlgorithm;
+use crate::core::routing::options::{
+    create_custom_weight_calculator, create_dimensions_weight_calculator, create_traffic_weight_calculator, create_vehicle_weight_calculator,
+    create_weight_calculator, AlgorithmType, RoutingAlgorithmOptions, WeightType,
+};
+use crate::core::routing::tddijkstra::TimeDependentDijkstraRoutingAlgorithm;
+use crate::core::routing::trip::{self, TripOptions, TripResult};
+use crate::core::routing::waypoints::{self, MultiLegRoutingResult, WaypointOptions};
+use crate::core::routing::Path;
+use crate::core::routing::RoutingResult;
+use crate::core::spatialindex::{EdgeIndex, DEFAULT_CELL_SIZE};
+use crate::core::traffic::{EdgeLookup, SpeedProfile, SpeedProfiles, SpeedUpdate, TrafficKey, TrafficOverlay};
+use crate::core::weight::{CustomRule, VehicleDimensions};
+use crate::core::Graph;
+use crate::reader::osm_reader::{OsmReader, ReaderOptions};
+use crate::reader::profile::Profile;
+use crate::writer::graph_writer::{self, ExportFormat, ExportOptions};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::rc::Rc;
 use std::time::Instant;
 
 pub struct Router<G: Graph> {
     graph: G,
-    kdtree: KdTree<f64, usize, [f64; 2]>,
+    edge_index: EdgeIndex,
+    traffic: Rc<TrafficOverlay>,
+    speed_profiles: Rc<SpeedProfiles>,
+    utc_offset: f64, //in seconds, for the conditional restrictions of the roads
+    vehicle_networks: HashMap<VehicleTypes, VehicleNetwork>,
+}
+
+//the nodes of the main network of a vehicle type and the spatial index over its edges
+struct VehicleNetwork {
+    nodes: HashSet<usize>,
+    edge_index: EdgeIndex,
 }
 
 pub fn new_router(file_name: &str) -> Router<impl Graph> {
+    new_router_with_profiles(file_name, vec![Profile::car()])
+}
+
+//the graph contains the roads of every profile, for example Profile::hgv() for route_truck
+pub fn new_router_with_profiles(file_name: &str, profiles: Vec<Profile>) -> Router<impl Graph> {
+    new_router_with_options(file_name, profiles, ReaderOptions::default())
+}
+
+//for example IdMapping::Bitsets to read large extracts with less memory
+pub fn new_router_with_options(file_name: &str, profiles: Vec<Profile>, options: ReaderOptions) -> Router<impl Graph> {
+    new_router_with_filter(file_name, profiles, options, ComponentsFilter::default())
+}
+
+//for example ComponentsPolicy::LargestPerIsland to route on islands that aren't connected to the largest component
+pub fn new_router_with_filter(file_name: &str, profiles: Vec<Profile>, options: ReaderOptions, filter: ComponentsFilter) -> Router<impl Graph> {
     let now = Instant::now();
+    let vehicle_types: Vec<VehicleTypes> = profiles.iter().map(|profile| profile.vehicle_type).collect();
 
     //TODO extract create graph logic ? also allow to use example graphs (the ones used for testing or so)
-    let result = OsmReader::new(file_name);
+    let result = OsmReader::with_options(file_name, profiles, options);
     let graph_reader = match result {
         Ok(graph_reader) => graph_reader,
         Err(e) => panic!("something went wrong while opening the osm file: {}", e),
@@ -32,53 +74,289 @@ pub fn new_router(file_name: &str) -> Router<impl Graph> {
     };
     println!("created graph in {} ms: nr edges={} & nr nodes={}", now.elapsed().as_millis(), graph.get_nr_edges(), graph.get_nr_nodes());
 
-    let now = Instant::now();
-    let opts = ComponentsAlgorithmOptions::new(ComponentsAlgorithmType::TARJAN);
-    let result = graph.get_strongly_connected_subgraphs(&opts);
-    println!("got {} components in {} ms", result.len(), now.elapsed().as_millis());
+    //every profile has its own main network, a node is kept if it is in at least one of them
+    let mut networks = HashMap::new();
+    for vehicle_type in vehicle_types {
+        let now = Instant::now();
+        let result = get_vehicle_components(&graph, vehicle_type, &ComponentsAlgorithmType::TARJAN);
+        println!("got {} components for {:?} in {} ms", result.len(), vehicle_type, now.elapsed().as_millis());
 
-    let mut max_size = 0;
-    let mut max_component = HashSet::new();
-    for component in result {
         //used for some debugging
-        if component.len() > 20 && component.len() < 5000 {
+        for component in result.iter().filter(|c| c.len() > 20 && c.len() < 5000) {
             println!("component has size {}", component.len());
             /*let visualisation = g.visualise_sub_graph(&component);
             println!("{}", visualisation);*/
         }
 
-        if component.len() > max_size {
-            max_size = component.len();
-            max_component = component;
-        }
+        networks.insert(vehicle_type, get_kept_nodes(&VehicleNeighbors::new(&graph, vehicle_type), result, filter.policy));
     }
 
-    println!("graph shrinking from {} nodes to {} nodes", graph.get_nr_nodes(), max_component.len());
-    let now = Instant::now();
-    graph.keep_nodes(&max_component);
-    println!("filtered graph in {} ms. current nr edges: {}", now.elapsed().as_millis(), graph.get_nr_edges());
+    let kept_nodes: HashSet<usize> = networks.values().flatten().copied().collect();
+    if filter.mark_only {
+        println!("keeping {} nodes, {} nodes are marked as not routable", graph.get_nr_nodes(), graph.get_nr_nodes() - kept_nodes.len());
+    } else {
+        println!("graph shrinking from {} nodes to {} nodes", graph.get_nr_nodes(), kept_nodes.len());
+        let now = Instant::now();
+        graph.keep_nodes(&kept_nodes);
+        println!("filtered graph in {} ms. current nr edges: {}", now.elapsed().as_millis(), graph.get_nr_edges());
+
+        //the remaining nodes keep their order
+        let mut old_nodes: Vec<usize> = kept_nodes.into_iter().collect();
+        old_nodes.sort_unstable();
+        let new_nodes: HashMap<usize, usize> = old_nodes.into_iter().enumerate().map(|(new, old)| (old, new)).collect();
+        for network in networks.values_mut() {
+            *network = network.iter().map(|node| new_nodes[node]).collect();
+        }
+    }
 
     let now = Instant::now();
-    let kdtree = graph.create_kd_tree();
-    println!("created kdtree in {} ms", now.elapsed().as_millis());
+    let router = Router::with_vehicle_networks(graph, networks);
+    println!("created spatial indices in {} ms", now.elapsed().as_millis());
 
-    Router { graph, kdtree }
+    router
 }
 
 impl<G: Graph> Router<G> {
+    //the graph is expected to be filtered already
+    pub fn new(graph: G) -> Self {
+        let edge_index = EdgeIndex::new(&graph, DEFAULT_CELL_SIZE);
+        Self::with_edge_index(graph, edge_index)
+    }
+
+    //the graph isn't filtered, but locations only snap to the edges between the routable nodes
+    pub fn with_routable_nodes(graph: G, routable_nodes: &HashSet<usize>) -> Self {
+        let edge_index = EdgeIndex::with_nodes(&graph, DEFAULT_CELL_SIZE, routable_nodes);
+        Self::with_edge_index(graph, edge_index)
+    }
+
+    //the main network of every vehicle type, see get_vehicle_components. Locations snap to the edges of the network of the vehicle type that is routed
+    pub fn with_vehicle_networks(graph: G, networks: HashMap<VehicleTypes, HashSet<usize>>) -> Self {
+        let routable_nodes: HashSet<usize> = networks.values().flatten().copied().collect();
+        let mut router = Self::with_routable_nodes(graph, &routable_nodes);
+
+        for (vehicle_type, nodes) in networks {
+            let edge_index = EdgeIndex::for_vehicle(&router.graph, DEFAULT_CELL_SIZE, vehicle_type, &nodes);
+            router.vehicle_networks.insert(vehicle_type, VehicleNetwork { nodes, edge_index });
+        }
+        router
+    }
+
+    fn with_edge_index(graph: G, edge_index: EdgeIndex) -> Self {
+        Router {
+            graph,
+            edge_index,
+            traffic: Rc::new(TrafficOverlay::new()),
+            speed_profiles: Rc::new(SpeedProfiles::new()),
+            utc_offset: 0.0,
+            vehicle_networks: HashMap::new(),
+        }
+    }
+
+    //every node is in the main network of every vehicle type if the router was created without networks
+    pub fn is_in_main_network(&self, node: usize, vehicle_type: VehicleTypes) -> bool {
+        if self.vehicle_networks.is_empty() {
+            return node < self.graph.get_nr_nodes();
+        }
+        self.vehicle_networks
+            .get(&vehicle_type)
+            .is_some_and(|network| network.nodes.contains(&node))
+    }
+
     pub fn route(&self, (from_lat, from_lon): (f64, f64), (to_lat, to_lon): (f64, f64)) -> Option<RoutingResult> {
-        let from_node = self.find_closest_node(from_lat, from_lon);
-        let to_node = self.find_closest_node(to_lat, to_lon);
+        let from_node = self.find_closest_node(from_lat, from_lon)?;
+        let to_node = self.find_closest_node(to_lat, to_lon)?;
         let opts = RoutingAlgorithmOptions::new(true, AlgorithmType::BIDIRDIJKSTRA, WeightType::DISTANCE);
         self.graph.route(&opts, from_node, to_node)
     }
 
+    //fastest route at the given time (seconds since the unix epoch), using the speed updates that haven't expired at that time
+    pub fn route_with_traffic(&self, (from_lat, from_lon): (f64, f64), (to_lat, to_lon): (f64, f64), time: f64) -> Option<RoutingResult> {
+        let from_node = self.find_closest_node(from_lat, from_lon)?;
+        let to_node = self.find_closest_node(to_lat, to_lon)?;
+
+        let weight_calculator = create_traffic_weight_calculator(VehicleTypes::Car, Rc::clone(&self.traffic), time);
+        let opts = RoutingAlgorithmOptions::with_weight_calculator(true, AlgorithmType::BIDIRDIJKSTRA, weight_calculator);
+        self.graph.route(&opts, from_node, to_node)
+    }
+
+    //the edges of the updates are osm node ids, nothing is applied if one of them isn't an edge of the graph
+    pub fn apply_speed_updates(&mut self, updates: Vec<SpeedUpdate>) -> Result<(), io::Error> {
+        let lookup = EdgeLookup::new(&self.graph);
+        let mut traffic = TrafficOverlay::clone(&self.traffic);
+        for update in updates {
+            traffic.apply(update, &lookup)?;
+        }
+
+        self.traffic = Rc::new(traffic);
+        Ok(())
+    }
+
+    //updates are only used at query times before they expire, so expired ones only take memory
+    //a feed can be applied ahead of time or replayed, so the time isn't the current time
+    pub fn remove_expired_speed_updates(&mut self, time: f64) {
+        Rc::make_mut(&mut self.traffic).remove_expired(time);
+    }
+
+    pub fn get_speed_updates(&self) -> Vec<&SpeedUpdate> {
+        self.traffic.get_updates()
+    }
+
+    pub fn clear_speed_updates(&mut self) {
+        self.traffic = Rc::new(TrafficOverlay::new());
+    }
+
+    //the profiles of read_speed_profiles, nothing is set if the nodes of an edge profile aren't an edge of the graph
+    pub fn set_speed_profiles(&mut self, profiles: Vec<(TrafficKey, Rc<SpeedProfile>)>) -> Result<(), io::Error> {
+        let lookup = EdgeLookup::new(&self.graph);
+        let mut speed_profiles = SpeedProfiles::new();
+        for (key, profile) in profiles {
+            speed_profiles.add(key, profile, &lookup)?;
+        }
+
+        self.speed_profiles = Rc::new(speed_profiles);
+        Ok(())
+    }
+
+    //the conditional restrictions of osm use local times, for example 3600 for Belgium in winter
+    pub fn set_utc_offset(&mut self, utc_offset: f64) {
+        self.utc_offset = utc_offset;
+    }
+
+    /// Fastest route when leaving at departure (seconds since the unix epoch)
+    /// roads with a speed profile use the speed at the time they are entered, other roads use the live traffic at departure
+    /// conditional restrictions are checked at the time a road is entered
+    pub fn route_at(&self, (from_lat, from_lon): (f64, f64), (to_lat, to_lon): (f64, f64), departure: f64) -> Option<RoutingResult> {
+        let from_node = self.find_closest_node(from_lat, from_lon)?;
+        let to_node = self.find_closest_node(to_lat, to_lon)?;
+
+        let opts = RoutingAlgorithmOptions {
+            routing_algorithm: Box::new(TimeDependentDijkstraRoutingAlgorithm {
+                path: true,
+                weight_calculator: create_traffic_weight_calculator(VehicleTypes::Car, Rc::clone(&self.traffic), departure),
+                speed_profiles: Rc::clone(&self.speed_profiles),
+                departure,
+                utc_offset: self.utc_offset,
+            }),
+        };
+        self.graph.route(&opts, from_node, to_node)
+    }
+
+    //fastest route with the preferences of the request, for example CustomRule::Ferry(0.0) avoids ferries and shuttle trains
+    pub fn route_custom(&self, (from_lat, from_lon): (f64, f64), (to_lat, to_lon): (f64, f64), rules: Vec<CustomRule>) -> Option<RoutingResult> {
+        let from_node = self.find_closest_node(from_lat, from_lon)?;
+        let to_node = self.find_closest_node(to_lat, to_lon)?;
+
+        let weight_calculator = create_custom_weight_calculator(&WeightType::TRAVELTIME, VehicleTypes::Car, rules);
+        let opts = RoutingAlgorithmOptions::with_weight_calculator(true, AlgorithmType::BIDIRDIJKSTRA, weight_calculator);
+        self.graph.route(&opts, from_node, to_node)
+    }
+
+    //fastest route for a truck that skips roads whose weight, size or hazmat limits it doesn't meet
+    //the graph must contain the hgv profile, see new_router_with_profiles
+    pub fn route_truck(
+        &self,
+        (from_lat, from_lon): (f64, f64),
+        (to_lat, to_lon): (f64, f64),
+        dimensions: VehicleDimensions,
+    ) -> Option<RoutingResult> {
+        let from_node = self.find_closest_vehicle_node(VehicleTypes::Hgv, from_lat, from_lon)?;
+        let to_node = self.find_closest_vehicle_node(VehicleTypes::Hgv, to_lat, to_lon)?;
+
+        let weight_calculator =
+            create_dimensions_weight_calculator(create_vehicle_weight_calculator(&WeightType::TRAVELTIME, VehicleTypes::Hgv), dimensions);
+        let opts = RoutingAlgorithmOptions::with_weight_calculator(true, AlgorithmType::BIDIRDIJKSTRA, weight_calculator);
+        self.graph.route(&opts, from_node, to_node)
+    }
+
+    //route that doesn't use any road intersecting the areas
+    pub fn route_avoiding(&self, (from_lat, from_lon): (f64, f64), (to_lat, to_lon): (f64, f64), areas: &[Area]) -> Option<RoutingResult> {
+        let from_node = self.find_closest_node(from_lat, from_lon)?;
+        let to_node = self.find_closest_node(to_lat, to_lon)?;
+
+        let weight_calculator =
+            create_avoid_areas_weight_calculator(create_weight_calculator(&WeightType::DISTANCE), &self.graph, &self.edge_index, areas);
+        let opts = RoutingAlgorithmOptions::with_weight_calculator(true, AlgorithmType::BIDIRDIJKSTRA, weight_calculator);
+        self.graph.route(&opts, from_node, to_node)
+    }
+
+    //route through all given coordinates in order
+    pub fn route_via(&self, points: &[(f64, f64)], waypoint_opts: &WaypointOptions) -> Option<MultiLegRoutingResult> {
+        let nodes: Vec<usize> = points
+            .iter()
+            .map(|(lat, lon)| self.find_closest_node(*lat, *lon))
+            .collect::<Option<_>>()?;
+        let opts = RoutingAlgorithmOptions::new(true, AlgorithmType::BIDIRDIJKSTRA, WeightType::DISTANCE);
+        waypoints::route_via(&self.graph, &opts, &nodes, waypoint_opts)
+    }
+
+    //visit all given coordinates in a short order, the first coordinate is the start
+    pub fn optimize_trip(&self, points: &[(f64, f64)], trip_opts: &TripOptions) -> Option<TripResult> {
+        let nodes: Vec<usize> = points
+            .iter()
+            .map(|(lat, lon)| self.find_closest_node(*lat, *lon))
+            .collect::<Option<_>>()?;
+        trip::optimize_trip(&self.graph, &WeightType::DISTANCE, &nodes, trip_opts)
+    }
+
+    //reconstruct the roads that were driven from a gps trace
+    pub fn match_trace(&self, points: &[GpsPoint], opts: &MapMatchingOptions) -> MapMatchingResult {
+        matching::match_trace(&self.graph, &self.edge_index, points, opts)
+    }
+
+    //travel time based routes from every source to the target, with a single backward search
+    pub fn route_many_to_one(&self, sources: &[(f64, f64)], (to_lat, to_lon): (f64, f64)) -> Vec<Option<RoutingResult>> {
+        let source_nodes: Option<Vec<usize>> = sources.iter().map(|(lat, lon)| self.find_closest_node(*lat, *lon)).collect();
+        let (Some(source_nodes), Some(to_node)) = (source_nodes, self.find_closest_node(to_lat, to_lon)) else {
+            return sources.iter().map(|_| None).collect();
+        };
+
+        let algorithm = DijkstraRoutingAlgorithm {
+            path: true,
+            weight_calculator: create_weight_calculator(&WeightType::TRAVELTIME),
+        };
+        algorithm.route_many_to_one(&self.graph, &source_nodes, to_node, f64::INFINITY)
+    }
+
+    //the k closest of the given locations, e.g. charging stations, by distance over the graph
+    //every hit is the index of the target in targets together with the route to it
+    pub fn route_k_closest(&self, (from_lat, from_lon): (f64, f64), targets: &[(f64, f64)], k: usize) -> Vec<(usize, RoutingResult)> {
+        let target_nodes: Option<Vec<usize>> = targets.iter().map(|(lat, lon)| self.find_closest_node(*lat, *lon)).collect();
+        let (Some(from_node), Some(target_nodes)) = (self.find_closest_node(from_lat, from_lon), target_nodes) else {
+            return vec![];
+        };
+
+        let algorithm = DijkstraRoutingAlgorithm {
+            path: true,
+            weight_calculator: create_weight_calculator(&WeightType::DISTANCE),
+        };
+        algorithm.route_k_closest(&self.graph, from_node, &target_nodes, k, f64::INFINITY)
+    }
+
     pub fn get_wkt(&self, path: &Path) -> String {
         path.get_wkt(&self.graph)
     }
 
-    fn find_closest_node(&self, lat: f64, lon: f64) -> usize {
-        let kd_nodes = self.kdtree.nearest(&[lat, lon], 1, &squared_euclidean).unwrap();
-        *kd_nodes[0].1
+    //writes the edges of the graph to a file that qgis can open, see ExportOptions for the filters
+    pub fn export_graph(&self, options: &ExportOptions, format: ExportFormat, file_name: &str) -> Result<(), io::Error> {
+        graph_writer::export_graph(&self.graph, options, format, file_name)
+    }
+
+    fn find_closest_node(&self, lat: f64, lon: f64) -> Option<usize> {
+        self.find_closest_vehicle_node(VehicleTypes::Car, lat, lon)
+    }
+
+    //snaps to the closest edge of the network of the vehicle type and takes the node of that edge that is closest to the projected point
+    //None if the network of the vehicle type has no edges, then there is no route for it
+    fn find_closest_vehicle_node(&self, vehicle_type: VehicleTypes, lat: f64, lon: f64) -> Option<usize> {
+        let edge_index = self
+            .vehicle_networks
+            .get(&vehicle_type)
+            .map_or(&self.edge_index, |network| &network.edge_index);
+        let hit = edge_index.nearest(lat, lon, 1).into_iter().next()?;
+        if hit.projection.fraction <= 0.5 {
+            Some(hit.node1)
+        } else {
+            Some(hit.node2)
+        }
     }
 }
@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use serde_json::json;
+
+use crate::core::avoidareas::Area;
+use crate::core::edge::VehicleTypes;
+use crate::core::Graph;
+
+//exports the edges of a graph for gis tools like qgis, one line per pair of connected nodes
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    GeoJson, //a FeatureCollection of LineStrings
+    Csv,     //the coordinates of both nodes in separate columns
+    WktCsv,  //a wkt LINESTRING in the last column, can be loaded as delimited text or converted to a geopackage with ogr2ogr
+}
+
+/// Which edges are exported and which properties they get
+/// an edge is exported if the vehicle type can use it in at least one direction, one of its nodes is inside the area and both nodes are in the node set
+pub struct ExportOptions {
+    pub vehicle_type: VehicleTypes,      //for the direction flags and speeds
+    pub area: Option<Area>,              //for example an Area::BBox around the problem
+    pub nodes: Option<HashSet<usize>>,   //for example a single component
+    pub components: Vec<HashSet<usize>>, //the component of an edge is its index in this list, see Graph::get_strongly_connected_subgraphs
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            vehicle_type: VehicleTypes::Car,
+            area: None,
+            nodes: None,
+            components: vec![],
+        }
+    }
+}
+
+//the properties of one exported line
+struct ExportedEdge {
+    from: usize,
+    to: usize,
+    from_osm_id: i64,
+    to_osm_id: i64,
+    from_location: (f64, f64), //(lat, lon)
+    to_location: (f64, f64),
+    distance: f64,
+    forward: bool,
+    backward: bool,
+    forward_speed: Option<f64>, //in km/h, None if the direction can't be used
+    backward_speed: Option<f64>,
+    component: Option<usize>, //None if both nodes aren't in the same component
+    way_id: Option<i64>,
+    road_class: String,
+}
+
+pub fn export_graph(g: &impl Graph, options: &ExportOptions, format: ExportFormat, file_name: &str) -> Result<(), io::Error> {
+    let mut writer = BufWriter::new(File::create(file_name)?);
+    write_graph(g, options, format, &mut writer)?;
+    writer.flush()
+}
+
+pub fn write_graph(g: &impl Graph, options: &ExportOptions, format: ExportFormat, writer: &mut impl Write) -> Result<(), io::Error> {
+    let edges = collect_edges(g, options);
+    match format {
+        ExportFormat::GeoJson => write_geojson(&edges, writer),
+        ExportFormat::Csv | ExportFormat::WktCsv => write_csv(&edges, format == ExportFormat::WktCsv, writer),
+    }
+}
+
+fn collect_edges(g: &impl Graph, options: &ExportOptions) -> Vec<ExportedEdge> {
+    let nr_nodes = g.get_nr_nodes();
+    let mut components = vec![None; nr_nodes];
+    for (component, nodes) in options.components.iter().enumerate() {
+        for node in nodes.iter().filter(|n| **n < nr_nodes) {
+            components[*node] = Some(component);
+        }
+    }
+
+    let in_area = |node: usize| {
+        let node = g.get_node(node).unwrap();
+        options.area.as_ref().is_none_or(|area| area.contains(node.lat, node.lon))
+    };
+    let in_nodes = |node: usize| options.nodes.as_ref().is_none_or(|nodes| nodes.contains(&node));
+
+    let mut edges = vec![];
+    for base_node in 0..nr_nodes {
+        //an edge that is only accessible backwards is only a neighbor of its adj node
+        let mut adj_nodes = HashSet::new();
+        g.do_for_all_neighbors(base_node, false, |adj_node| {
+            adj_nodes.insert(adj_node);
+        });
+        g.do_for_all_neighbors(base_node, true, |adj_node| {
+            adj_nodes.insert(adj_node);
+        });
+
+        let mut adj_nodes: Vec<usize> = adj_nodes.into_iter().filter(|adj_node| base_node <= *adj_node).collect();
+        adj_nodes.sort_unstable();
+
+        for adj_node in adj_nodes {
+            if !(in_area(base_node) || in_area(adj_node)) || !in_nodes(base_node) || !in_nodes(adj_node) {
+                continue;
+            }
+
+            let forward = g.get_directed_vehicle_specific_edge_information(base_node, adj_node, false, options.vehicle_type);
+            let backward = g.get_directed_vehicle_specific_edge_information(adj_node, base_node, false, options.vehicle_type);
+            let Some(info) = forward.as_ref().or(backward.as_ref()) else {
+                continue;
+            };
+
+            let (from, to) = (g.get_node(base_node).unwrap(), g.get_node(adj_node).unwrap());
+            edges.push(ExportedEdge {
+                from: base_node,
+                to: adj_node,
+                from_osm_id: from.osm_id,
+                to_osm_id: to.osm_id,
+                from_location: (from.lat, from.lon),
+                to_location: (to.lat, to.lon),
+                distance: info.get_distance(),
+                forward: forward.as_ref().is_some_and(|f| f.is_accessible()),
+                backward: backward.as_ref().is_some_and(|b| b.is_accessible()),
+                forward_speed: forward.as_ref().map(|f| f.get_speed() * 3.6),
+                backward_speed: backward.as_ref().map(|b| b.get_speed() * 3.6),
+                component: components[base_node].filter(|c| components[adj_node] == Some(*c)),
+                way_id: info.get_attributes().way_id,
+                road_class: format!("{:?}", info.get_attributes().road_class).to_lowercase(),
+            });
+        }
+    }
+
+    edges
+}
+
+fn write_geojson(edges: &[ExportedEdge], writer: &mut impl Write) -> Result<(), io::Error> {
+    writeln!(writer, "{{\"type\":\"FeatureCollection\",\"features\":[")?;
+    for (i, edge) in edges.iter().enumerate() {
+        let feature = json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "LineString",
+                "coordinates": [[edge.from_location.1, edge.from_location.0], [edge.to_location.1, edge.to_location.0]],
+            },
+            "properties": {
+                "from": edge.from,
+                "to": edge.to,
+                "from_osm_id": edge.from_osm_id,
+                "to_osm_id": edge.to_osm_id,
+                "distance": edge.distance,
+                "forward": edge.forward,
+                "backward": edge.backward,
+                "forward_speed": edge.forward_speed,
+                "backward_speed": edge.backward_speed,
+                "component": edge.component,
+                "way_id": edge.way_id,
+                "road_class": edge.road_class,
+            },
+        });
+        let separator = if i + 1 < edges.len() { "," } else { "" };
+        writeln!(writer, "{}{}", feature, separator)?;
+    }
+    writeln!(writer, "]}}")
+}
+
+fn write_csv(edges: &[ExportedEdge], wkt: bool, writer: &mut impl Write) -> Result<(), io::Error> {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+
+    let location_columns = if wkt { "geometry" } else { "from_lat,from_lon,to_lat,to_lon" };
+    writeln!(
+        writer,
+        "from,to,from_osm_id,to_osm_id,distance,forward,backward,forward_speed,backward_speed,component,way_id,road_class,{}",
+        location_columns
+    )?;
+
+    for edge in edges {
+        let (from, to) = (edge.from_location, edge.to_location);
+        let locations = if wkt {
+            format!("\"LINESTRING ({:.7} {:.7}, {:.7} {:.7})\"", from.1, from.0, to.1, to.0)
+        } else {
+            format!("{:.7},{:.7},{:.7},{:.7}", from.0, from.1, to.0, to.1)
+        };
+
+        writeln!(
+            writer,
+            "{},{},{},{},{:.2},{},{},{},{},{},{},{},{}",
+            edge.from,
+            edge.to,
+            edge.from_osm_id,
+            edge.to_osm_id,
+            edge.distance,
+            edge.forward,
+            edge.backward,
+            optional(edge.forward_speed.map(|s| format!("{:.2}", s))),
+            optional(edge.backward_speed.map(|s| format!("{:.2}", s))),
+            optional(edge.component.map(|c| c.to_string())),
+            optional(edge.way_id.map(|w| w.to_string())),
+            edge.road_class,
+            locations
+        )?;
+    }
+
+    Ok(())
+}
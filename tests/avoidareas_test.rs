@@ -0,0 +1,72 @@
+use std::collections::HashSet;
+
+use perionav::core::avoidareas::{create_avoid_areas_weight_calculator, find_blocked_edges, point_in_polygon, Area};
+use perionav::core::routing::options::{create_weight_calculator, AlgorithmType, RoutingAlgorithmOptions, WeightType};
+use perionav::core::spatialindex::{EdgeIndex, DEFAULT_CELL_SIZE};
+use perionav::core::Graph;
+use perionav::router::Router;
+use rstest::rstest;
+
+mod create_graph;
+
+//a small box around the middle of the edge between 0 and 1
+fn middle_of_first_edge() -> Area {
+    Area::BBox {
+        min_lat: 50.9999,
+        min_lon: 3.7006,
+        max_lat: 51.0001,
+        max_lon: 3.7008,
+    }
+}
+
+#[test]
+fn test_point_in_polygon() {
+    let square = vec![(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)];
+    assert!(point_in_polygon((0.5, 0.5), &square));
+    assert!(!point_in_polygon((1.5, 0.5), &square));
+    assert!(!point_in_polygon((0.5, 0.5), &[]));
+}
+
+#[test]
+fn test_find_blocked_edges() {
+    let g = create_graph::create_coordinates_grid_graph();
+    let index = EdgeIndex::new(&g, DEFAULT_CELL_SIZE);
+
+    assert_eq!(find_blocked_edges(&g, &index, &[middle_of_first_edge()]), HashSet::from([(0, 1)]));
+
+    //a triangle around node 4 blocks all of its edges
+    let triangle = Area::Polygon(vec![(51.0005, 3.701), (51.0005, 3.7018), (51.0013, 3.7014)]);
+    assert_eq!(find_blocked_edges(&g, &index, &[triangle]), HashSet::from([(1, 4), (3, 4), (4, 5), (4, 7)]));
+
+    //a thin polygon that crosses the edge between 0 and 1 without containing a node
+    let thin = Area::Polygon(vec![(50.9995, 3.7007), (51.0005, 3.7007), (51.0005, 3.70071)]);
+    assert_eq!(find_blocked_edges(&g, &index, &[thin]), HashSet::from([(0, 1)]));
+}
+
+#[rstest]
+fn test_route_avoiding(#[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType) {
+    let g = create_graph::create_coordinates_grid_graph();
+    let index = EdgeIndex::new(&g, DEFAULT_CELL_SIZE);
+
+    let weight_calculator =
+        create_avoid_areas_weight_calculator(create_weight_calculator(&WeightType::DISTANCE), &g, &index, &[middle_of_first_edge()]);
+    let opts = RoutingAlgorithmOptions::with_weight_calculator(true, algorithm_type, weight_calculator);
+
+    //instead of 0 1 2 the route has to go around through the second row
+    let result = g.route(&opts, 0, 2).unwrap();
+    let nodes = result.paths[0].get_nodes();
+    assert_eq!(nodes.len(), 5);
+    assert_eq!(&nodes[..3], &[0, 3, 4]);
+    assert_eq!(nodes[4], 2);
+}
+
+#[test]
+fn test_router_route_avoiding() {
+    let router = Router::new(create_graph::create_coordinates_grid_graph());
+
+    let result = router.route((51.0, 3.7), (51.0, 3.7028)).unwrap();
+    assert_eq!(result.paths[0].get_nodes(), vec![0, 1, 2]);
+
+    let result = router.route_avoiding((51.0, 3.7), (51.0, 3.7028), &[middle_of_first_edge()]).unwrap();
+    assert_eq!(result.paths[0].get_nodes().len(), 5);
+}
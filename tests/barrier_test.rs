@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+
+use perionav::core::edge::VehicleTypes;
+use perionav::core::routing::options::{create_vehicle_weight_calculator, AlgorithmType, RoutingAlgorithmOptions, WeightType};
+use perionav::core::Graph;
+use perionav::reader::osm_reader::OsmReader;
+use perionav::reader::profile::Profile;
+use rstest::rstest;
+
+mod create_graph;
+
+fn route(algorithm_type: AlgorithmType, weight_type: WeightType, vehicle_type: VehicleTypes) -> (f64, Vec<usize>) {
+    let g = create_graph::create_barrier_graph();
+    let weight_calculator = create_vehicle_weight_calculator(&weight_type, vehicle_type);
+    let opts = RoutingAlgorithmOptions::with_weight_calculator(true, algorithm_type, weight_calculator);
+
+    let result = g.route(&opts, 0, 3).unwrap();
+    (result.weight, result.paths[0].get_nodes())
+}
+
+#[rstest]
+//bikes pass the bollard, cars can't
+#[case(WeightType::TRAVELTIME, VehicleTypes::Bike, (20.0, vec![0, 1, 2, 3]))]
+#[case(WeightType::DISTANCE, VehicleTypes::Car, (30.0, vec![0, 4, 5, 3]))]
+//the toll booth adds 30s to the travel time, but not to the distance
+#[case(WeightType::TRAVELTIME, VehicleTypes::Car, (50.0, vec![0, 6, 3]))]
+fn test_barriers(
+    #[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType,
+    #[case] weight_type: WeightType,
+    #[case] vehicle_type: VehicleTypes,
+    #[case] expected: (f64, Vec<usize>),
+) {
+    assert_eq!(route(algorithm_type, weight_type, vehicle_type), expected);
+}
+
+//(osm id, osm id, time penalty for cars or None if cars can't use it) of every directed edge
+//bikes pass every barrier so that the edges stay in the graph
+fn read_edges(file_name: &str) -> Vec<(i64, i64, Option<f64>)> {
+    let bike = Profile {
+        vehicle_type: VehicleTypes::Bike,
+        blocked_barriers: HashSet::new(),
+        ..Profile::car()
+    };
+    let g = OsmReader::with_profiles(file_name, vec![Profile::car(), bike])
+        .unwrap()
+        .read_graph()
+        .unwrap();
+    let osm_id = |node: usize| g.get_node(node).unwrap().osm_id;
+
+    let mut edges = vec![];
+    for node in 0..g.get_nr_nodes() {
+        g.do_for_all_neighbors(node, false, |adj_node| {
+            let info = g.get_directed_vehicle_specific_edge_information(node, adj_node, false, VehicleTypes::Car);
+            edges.push((osm_id(node), osm_id(adj_node), info.map(|i| i.get_time_penalty())));
+        });
+    }
+    edges.sort_by_key(|(from, to, _)| (*from, *to));
+    edges
+}
+
+#[test]
+fn test_read_barriers() {
+    let edges = read_edges("tests/data/barrier_network.osm");
+
+    //the bollard in the middle of way 101 is a copy of node 2, with an edge of length 0 in between that cars can't use
+    assert!(edges.contains(&(2, 2, None)));
+    assert!(edges.contains(&(1, 2, Some(0.0))));
+    assert!(edges.contains(&(2, 1, Some(0.0))));
+}
+
+#[test]
+fn test_junction_barrier() {
+    let edges = read_edges("tests/data/barrier_network.osm");
+
+    //the bollard at 3 is in the middle of way 101 and way 102: cars can't enter 3 from any side, so they can't drive through it
+    for from in [2, 4, 5, 6] {
+        assert!(edges.contains(&(from, 3, None)));
+        assert!(edges.contains(&(3, from, Some(0.0))));
+    }
+}
+
+#[test]
+fn test_shared_way_end_barrier() {
+    let edges = read_edges("tests/data/barrier_network.osm");
+
+    //the toll booth at 7 ends way 103 and starts way 104: only the edges that enter 7 have the penalty, so it is paid once
+    assert!(edges.contains(&(4, 7, Some(30.0))));
+    assert!(edges.contains(&(7, 8, Some(0.0))));
+    assert!(edges.contains(&(8, 7, Some(30.0))));
+    assert!(edges.contains(&(7, 4, Some(0.0))));
+}
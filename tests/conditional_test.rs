@@ -0,0 +1,66 @@
+use std::rc::Rc;
+
+use perionav::core::routing::options::{
+    create_conditional_weight_calculator, create_weight_calculator, AlgorithmType, RoutingAlgorithmOptions, WeightType,
+};
+use perionav::core::routing::tddijkstra::TimeDependentDijkstraRoutingAlgorithm;
+use perionav::core::traffic::SpeedProfiles;
+use perionav::core::Graph;
+use rstest::rstest;
+
+mod create_graph;
+
+const MONDAY: f64 = 1709510400.0; //2024-03-04T00:00:00Z
+const HOUR: f64 = 3600.0;
+
+fn route(algorithm_type: AlgorithmType, local_time: Option<f64>, end: usize) -> Option<(f64, Vec<usize>)> {
+    let g = create_graph::create_conditional_graph();
+    let weight_calculator = create_weight_calculator(&WeightType::DISTANCE);
+    let weight_calculator = match local_time {
+        Some(time) => create_conditional_weight_calculator(weight_calculator, time),
+        None => weight_calculator,
+    };
+    let opts = RoutingAlgorithmOptions::with_weight_calculator(true, algorithm_type, weight_calculator);
+
+    g.route(&opts, 0, end).map(|result| (result.weight, result.paths[0].get_nodes()))
+}
+
+#[rstest]
+//without a time the conditions are ignored
+#[case(None, 2, Some((10.0, vec![0, 2])))]
+#[case(Some(MONDAY + 8.0 * HOUR), 2, Some((16.0, vec![0, 1, 2])))]
+#[case(Some(MONDAY + 9.0 * HOUR), 2, Some((10.0, vec![0, 2])))]
+//the school street isn't closed on saturday
+#[case(Some(MONDAY + 5.0 * 24.0 * HOUR + 8.0 * HOUR), 2, Some((10.0, vec![0, 2])))]
+//the pedestrian zone can only be used during delivery hours
+#[case(None, 3, None)]
+#[case(Some(MONDAY + 12.0 * HOUR), 3, None)]
+#[case(Some(MONDAY + 10.0 * HOUR), 3, Some((15.0, vec![0, 2, 3])))]
+fn test_conditional(
+    #[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType,
+    #[case] local_time: Option<f64>,
+    #[case] end: usize,
+    #[case] expected: Option<(f64, Vec<usize>)>,
+) {
+    assert_eq!(route(algorithm_type, local_time, end), expected);
+}
+
+#[rstest]
+#[case(MONDAY + 7.0 * HOUR, 10.0)]
+#[case(MONDAY + 7.75 * HOUR - 1.0, 10.0)]
+#[case(MONDAY + 7.75 * HOUR, 16.0)]
+fn test_time_dependent(#[case] departure: f64, #[case] expected: f64) {
+    //the restriction is checked when the edge is entered, the utc offset moves the local time one hour ahead
+    let g = create_graph::create_conditional_graph();
+    let opts = RoutingAlgorithmOptions {
+        routing_algorithm: Box::new(TimeDependentDijkstraRoutingAlgorithm {
+            path: false,
+            weight_calculator: create_weight_calculator(&WeightType::TRAVELTIME),
+            speed_profiles: Rc::new(SpeedProfiles::new()),
+            departure: departure - HOUR,
+            utc_offset: HOUR,
+        }),
+    };
+
+    assert_eq!(g.route(&opts, 0, 2).unwrap().weight, expected);
+}
@@ -1,4 +1,8 @@
-use perionav::core::edge::Edge;
+#![allow(dead_code)]
+
+use perionav::core::conditional::{ConditionalRestriction, OpeningHours};
+use perionav::core::edge::{Edge, EdgeAttributes, PhysicalRestrictions, RoadClass, VehicleAccess, VehicleTypes};
+use perionav::core::geometry::haversine_distance;
 use perionav::core::graph::Graph;
 use perionav::core::node::Node;
 use perionav::core::standardgraph::StandardGraph;
@@ -74,6 +78,221 @@ pub fn create_small_sub_components_graph() -> impl Graph {
     g
 }
 
+//0 -> 1 -> 2 can be used by cars and bikes, the shortcut 0 -> 2 only by bikes
+//bikes are 4 times slower than cars
+pub fn create_vehicles_graph() -> impl Graph {
+    let mut g = StandardGraph::new(100);
+    g.add_node(Node::default());
+    g.add_node(Node::default());
+    g.add_node(Node::default());
+
+    let access = |forward, backward, speed| VehicleAccess {
+        forward,
+        backward,
+        forward_speed: speed,
+        backward_speed: speed,
+        conditions: vec![],
+    };
+
+    let both = |distance| {
+        Edge::with_vehicle_access(
+            distance,
+            vec![
+                (VehicleTypes::Car, access(true, true, 8.0)),
+                (VehicleTypes::Bike, access(true, true, 2.0)),
+            ],
+        )
+    };
+    g.add_edge(0, 1, both(4.0));
+    g.add_edge(1, 2, both(4.0));
+    g.add_edge(0, 2, Edge::with_vehicle_access(2.0, vec![(VehicleTypes::Bike, access(true, false, 2.0))]));
+
+    g
+}
+
+//0 -> 1 -> 3 is a toll motorway (way 1) of 20m at 30m/s, 0 -> 2 -> 3 a residential road (way 2) of 24m at 10m/s
+pub fn create_road_classes_graph() -> impl Graph {
+    //the osm ids are 10 to 13, so that they differ from the indices
+    let mut g = StandardGraph::new(4);
+    let locations = [(51.0, 3.7), (51.0005, 3.7005), (51.0005, 3.6995), (51.001, 3.7)];
+    for (i, (lat, lon)) in locations.into_iter().enumerate() {
+        g.add_node(Node::new(10 + i as i64, lat, lon));
+    }
+
+    let edge = |distance, speed, road_class, toll, way_id| {
+        let access = VehicleAccess {
+            forward: true,
+            backward: true,
+            forward_speed: speed,
+            backward_speed: speed,
+            conditions: vec![],
+        };
+        let attributes = EdgeAttributes {
+            road_class,
+            toll,
+            ferry: false,
+            way_id: Some(way_id),
+            restrictions: PhysicalRestrictions::default(),
+            time_penalty: 0.0,
+        };
+        Edge::with_attributes(distance, attributes, vec![(VehicleTypes::Car, access)])
+    };
+
+    g.add_edge(0, 1, edge(10.0, 30.0, RoadClass::Motorway, true, 1));
+    g.add_edge(1, 3, edge(10.0, 30.0, RoadClass::Motorway, false, 1));
+    g.add_edge(0, 2, edge(12.0, 10.0, RoadClass::Residential, false, 2));
+    g.add_edge(2, 3, edge(12.0, 10.0, RoadClass::Residential, false, 2));
+
+    g
+}
+
+//0 -> 2 is a school street that is closed mo-fr 07:45-08:30, 0 -> 1 -> 2 is longer but always open
+//2 -> 3 is a pedestrian zone that only allows deliveries from 05:00 to 11:00
+pub fn create_conditional_graph() -> impl Graph {
+    let mut g = StandardGraph::new(4);
+    for _ in 0..4 {
+        g.add_node(Node::default());
+    }
+
+    let edge = |distance, accessible, forward, backward, hours| {
+        let access = VehicleAccess {
+            forward: accessible,
+            backward: accessible,
+            forward_speed: 1.0,
+            backward_speed: 1.0,
+            conditions: vec![ConditionalRestriction {
+                forward,
+                backward,
+                hours: OpeningHours::parse(hours).unwrap(),
+            }],
+        };
+        Edge::with_vehicle_access(distance, vec![(VehicleTypes::Car, access)])
+    };
+
+    g.add_edge(0, 1, Edge::new(8.0, true, true));
+    g.add_edge(1, 2, Edge::new(8.0, true, true));
+    g.add_edge(0, 2, edge(10.0, true, Some(false), Some(false), "Mo-Fr 07:45-08:30"));
+    g.add_edge(2, 3, edge(5.0, false, Some(true), Some(true), "05:00-11:00"));
+
+    g
+}
+
+//0 -> 1 -> 3 is 20m at 1 m/s and 0 -> 2 -> 3 is 30m at 10 m/s. 3 -> 4 is 20m at 10 m/s in the direction of the edge and 2 m/s in the other direction
+pub fn create_speeds_graph() -> impl Graph {
+    let mut g = StandardGraph::new(5);
+    for _ in 0..5 {
+        g.add_node(Node::default());
+    }
+
+    let edge = |distance, forward_speed, backward_speed| {
+        let access = VehicleAccess {
+            forward: true,
+            backward: true,
+            forward_speed,
+            backward_speed,
+            conditions: vec![],
+        };
+        Edge::with_vehicle_access(distance, vec![(VehicleTypes::Car, access)])
+    };
+
+    g.add_edge(0, 1, edge(10.0, 1.0, 1.0));
+    g.add_edge(1, 3, edge(10.0, 1.0, 1.0));
+    g.add_edge(0, 2, edge(15.0, 10.0, 10.0));
+    g.add_edge(2, 3, edge(15.0, 10.0, 10.0));
+    g.add_edge(3, 4, edge(20.0, 10.0, 2.0));
+
+    g
+}
+
+//three routes for trucks from 0 to 3: 0 -> 1 -> 3 of 20m under a bridge of 3.5m,
+//0 -> 2 -> 3 of 24m with a weight limit of 7.5t and no hazardous goods, and 0 -> 4 -> 3 of 40m with a width limit of 2.55m
+pub fn create_truck_graph() -> impl Graph {
+    let mut g = StandardGraph::new(5);
+    for _ in 0..5 {
+        g.add_node(Node::default());
+    }
+
+    let edge = |distance, restrictions| {
+        let access = VehicleAccess {
+            forward: true,
+            backward: true,
+            forward_speed: 1.0,
+            backward_speed: 1.0,
+            conditions: vec![],
+        };
+        let attributes = EdgeAttributes {
+            restrictions,
+            ..Default::default()
+        };
+        Edge::with_attributes(distance, attributes, vec![(VehicleTypes::Hgv, access)])
+    };
+
+    let bridge = PhysicalRestrictions {
+        max_height: Some(3.5),
+        ..Default::default()
+    };
+    let weight_limit = PhysicalRestrictions {
+        max_weight: Some(7.5),
+        no_hazmat: true,
+        ..Default::default()
+    };
+
+    g.add_edge(0, 1, edge(10.0, PhysicalRestrictions::default()));
+    g.add_edge(1, 3, edge(10.0, bridge));
+    g.add_edge(0, 2, edge(12.0, PhysicalRestrictions::default()));
+    g.add_edge(2, 3, edge(12.0, weight_limit));
+    g.add_edge(0, 4, edge(20.0, PhysicalRestrictions::default()));
+    g.add_edge(
+        4,
+        3,
+        edge(
+            20.0,
+            PhysicalRestrictions {
+                max_width: Some(2.55),
+                ..Default::default()
+            },
+        ),
+    );
+
+    g
+}
+
+//barriers are edges of length 0 between 2 copies of the barrier node
+//0 -> 1 -> 2 -> 3 has a bollard (1 -> 2) that only bikes can pass, 0 -> 4 -> 5 -> 3 a toll booth (4 -> 5) of 30s and 0 -> 6 -> 3 is 50m without barriers
+pub fn create_barrier_graph() -> impl Graph {
+    let mut g = StandardGraph::new(7);
+    for _ in 0..7 {
+        g.add_node(Node::default());
+    }
+
+    let access = VehicleAccess {
+        forward: true,
+        backward: true,
+        forward_speed: 1.0,
+        backward_speed: 1.0,
+        conditions: vec![],
+    };
+    let edge = |distance, time_penalty, vehicle_types: &[VehicleTypes]| {
+        let attributes = EdgeAttributes {
+            time_penalty,
+            ..Default::default()
+        };
+        Edge::with_attributes(distance, attributes, vehicle_types.iter().map(|v| (*v, access.clone())).collect())
+    };
+    let all = [VehicleTypes::Car, VehicleTypes::Bike];
+
+    g.add_edge(0, 1, edge(10.0, 0.0, &all));
+    g.add_edge(1, 2, edge(0.0, 0.0, &[VehicleTypes::Bike]));
+    g.add_edge(2, 3, edge(10.0, 0.0, &all));
+    g.add_edge(0, 4, edge(15.0, 0.0, &all));
+    g.add_edge(4, 5, edge(0.0, 30.0, &all));
+    g.add_edge(5, 3, edge(15.0, 0.0, &all));
+    g.add_edge(0, 6, edge(25.0, 0.0, &all));
+    g.add_edge(6, 3, edge(25.0, 0.0, &all));
+
+    g
+}
+
 pub fn create_sub_components_graph() -> impl Graph {
     let mut g = StandardGraph::new(100);
     g.add_node(Node::default());
@@ -209,3 +428,133 @@ pub fn create_strongly_disconnected_graph() -> impl Graph {
 
     g
 }
+
+//a town square 0-1-2-3 with a single road from 2 to the village triangle 4-5-6, a dead end from 5 to 7 and a separate oneway from 8 to 9
+pub fn create_village_graph() -> impl Graph {
+    let mut g = StandardGraph::new(10);
+    for _ in 0..10 {
+        g.add_node(Node::default());
+    }
+
+    g.add_edge(0, 1, Edge::new(1.0, true, true));
+    g.add_edge(1, 2, Edge::new(1.0, true, true));
+    g.add_edge(2, 3, Edge::new(1.0, true, true));
+    g.add_edge(3, 0, Edge::new(1.0, true, false));
+
+    g.add_edge(2, 4, Edge::new(1.0, true, true));
+    g.add_edge(4, 5, Edge::new(1.0, true, true));
+    g.add_edge(5, 6, Edge::new(1.0, true, false));
+    g.add_edge(6, 4, Edge::new(1.0, true, true));
+    g.add_edge(7, 5, Edge::new(1.0, true, true));
+
+    g.add_edge(8, 9, Edge::new(1.0, true, false));
+
+    g
+}
+
+//a mainland square 0-1-2-3 with a oneway dead end from 3 to 4, an island triangle 5-6-7 and an islet 8-9 without a ferry to the mainland
+pub fn create_coordinates_islands_graph() -> StandardGraph {
+    let mut g = StandardGraph::new(10);
+    let locations = [
+        (51.0, 3.7),
+        (51.0, 3.701),
+        (51.001, 3.701),
+        (51.001, 3.7),
+        (51.002, 3.7),
+        (51.01, 3.7),
+        (51.01, 3.701),
+        (51.011, 3.7005),
+        (51.02, 3.7),
+        (51.02, 3.701),
+    ];
+    for (id, (lat, lon)) in locations.into_iter().enumerate() {
+        g.add_node(Node::new(id as i64, lat, lon));
+    }
+
+    g.add_edge(0, 1, Edge::new(70.0, true, true));
+    g.add_edge(1, 2, Edge::new(111.0, true, true));
+    g.add_edge(2, 3, Edge::new(70.0, true, true));
+    g.add_edge(3, 0, Edge::new(111.0, true, true));
+    g.add_edge(3, 4, Edge::new(111.0, true, false));
+
+    g.add_edge(5, 6, Edge::new(70.0, true, true));
+    g.add_edge(6, 7, Edge::new(120.0, true, true));
+    g.add_edge(7, 5, Edge::new(120.0, true, true));
+
+    g.add_edge(8, 9, Edge::new(70.0, true, true));
+
+    g
+}
+
+//the square 0-3 of create_coordinates_islands_graph for cars and bikes, 3 -> 4 is a oneway for cars that bikes can use in both directions
+//and 4 - 5 a cycleway, so 4 and 5 are only in the main network of bikes
+pub fn create_coordinates_cycleway_graph() -> StandardGraph {
+    let mut g = StandardGraph::new(6);
+    let locations = [(51.0, 3.7), (51.0, 3.701), (51.001, 3.701), (51.001, 3.7), (51.002, 3.7), (51.003, 3.7)];
+    for (id, (lat, lon)) in locations.into_iter().enumerate() {
+        g.add_node(Node::new(id as i64, lat, lon));
+    }
+
+    let access = |forward, backward| VehicleAccess {
+        forward,
+        backward,
+        forward_speed: 5.0,
+        backward_speed: 5.0,
+        conditions: vec![],
+    };
+    let edge = |distance, car: Option<VehicleAccess>| {
+        let mut vehicle_access = vec![(VehicleTypes::Bike, access(true, true))];
+        vehicle_access.extend(car.map(|car| (VehicleTypes::Car, car)));
+        Edge::with_vehicle_access(distance, vehicle_access)
+    };
+
+    g.add_edge(0, 1, edge(70.0, Some(access(true, true))));
+    g.add_edge(1, 2, edge(111.0, Some(access(true, true))));
+    g.add_edge(2, 3, edge(70.0, Some(access(true, true))));
+    g.add_edge(3, 0, edge(111.0, Some(access(true, true))));
+    g.add_edge(3, 4, edge(111.0, Some(access(true, false))));
+    g.add_edge(4, 5, edge(111.0, None));
+
+    g
+}
+
+//square with real coordinates: 0 is south west, 1 south east, 2 north west and 3 north east
+pub fn create_coordinates_square_graph() -> StandardGraph {
+    let mut g = StandardGraph::new(4);
+    g.add_node(Node::new(0, 51.0, 3.7));
+    g.add_node(Node::new(1, 51.0, 3.701));
+    g.add_node(Node::new(2, 51.001, 3.7));
+    g.add_node(Node::new(3, 51.001, 3.701));
+
+    g.add_edge(0, 1, Edge::new(1.0, true, true));
+    g.add_edge(1, 3, Edge::new(1.0, true, true));
+    g.add_edge(3, 2, Edge::new(1.0, true, true));
+    g.add_edge(2, 0, Edge::new(1.0, true, true));
+
+    g
+}
+
+//3x3 grid with roughly 100m between neighboring nodes. Node i is at row i / 3 (from south to north) and column i % 3 (from west to east)
+pub fn create_coordinates_grid_graph() -> StandardGraph {
+    let mut g = StandardGraph::new(9);
+    for i in 0..9 {
+        g.add_node(Node::new(i as i64, 51.0 + 0.0009 * (i / 3) as f64, 3.7 + 0.0014 * (i % 3) as f64));
+    }
+
+    let add_edge = |g: &mut StandardGraph, base: usize, adj: usize| {
+        let (from, to) = (g.get_node(base).unwrap(), g.get_node(adj).unwrap());
+        let distance = haversine_distance(from.lat, from.lon, to.lat, to.lon);
+        g.add_edge(base, adj, Edge::new(distance, true, true));
+    };
+
+    for i in 0..9 {
+        if i % 3 < 2 {
+            add_edge(&mut g, i, i + 1);
+        }
+        if i / 3 < 2 {
+            add_edge(&mut g, i, i + 3);
+        }
+    }
+
+    g
+}
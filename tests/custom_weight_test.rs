@@ -0,0 +1,59 @@
+use perionav::core::{
+    edge::{RoadClass, VehicleTypes},
+    routing::options::{create_custom_weight_calculator, AlgorithmType, RoutingAlgorithmOptions, WeightType},
+    weight::CustomRule,
+    Graph,
+};
+use rstest::rstest;
+
+mod create_graph;
+
+fn route(algorithm_type: AlgorithmType, weight_type: WeightType, rules: Vec<CustomRule>) -> (f64, Vec<usize>) {
+    let g = create_graph::create_road_classes_graph();
+    let weight_calculator = create_custom_weight_calculator(&weight_type, VehicleTypes::Car, rules);
+    let opts = RoutingAlgorithmOptions::with_weight_calculator(true, algorithm_type, weight_calculator);
+
+    let result = g.route(&opts, 0, 3).unwrap();
+    (result.weight, result.paths[0].get_nodes())
+}
+
+#[rstest]
+fn test_without_rules(#[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType) {
+    assert_eq!(route(algorithm_type, WeightType::DISTANCE, vec![]), (20.0, vec![0, 1, 3]));
+}
+
+#[rstest]
+fn test_avoid_toll(#[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType) {
+    assert_eq!(route(algorithm_type, WeightType::DISTANCE, vec![CustomRule::Toll(0.0)]), (24.0, vec![0, 2, 3]));
+}
+
+#[rstest]
+fn test_road_class_priority(
+    #[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType,
+) {
+    let rules = vec![CustomRule::RoadClass(RoadClass::Motorway, 0.5)];
+    assert_eq!(route(algorithm_type, WeightType::DISTANCE, rules), (24.0, vec![0, 2, 3]));
+}
+
+#[rstest]
+fn test_unused_rules(#[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType) {
+    //the motorway is still shorter, and a ferry rule doesn't change anything without ferries
+    let rules = vec![CustomRule::RoadClass(RoadClass::Residential, 0.8), CustomRule::Ferry(0.0)];
+    assert_eq!(route(algorithm_type, WeightType::DISTANCE, rules), (20.0, vec![0, 1, 3]));
+}
+
+#[test]
+fn test_max_speed() {
+    let (weight, nodes) = route(AlgorithmType::DIJKSTRA, WeightType::TRAVELTIME, vec![]);
+    assert!((weight - 20.0 / 30.0).abs() < 1E-9);
+    assert_eq!(nodes, vec![0, 1, 3]);
+
+    //36 km/h is 10 m/s
+    let (weight, nodes) = route(AlgorithmType::DIJKSTRA, WeightType::TRAVELTIME, vec![CustomRule::MaxSpeed(36.0)]);
+    assert!((weight - 2.0).abs() < 1E-9);
+    assert_eq!(nodes, vec![0, 1, 3]);
+
+    //the max speed doesn't change distance based weights
+    let (weight, _) = route(AlgorithmType::DIJKSTRA, WeightType::DISTANCE, vec![CustomRule::MaxSpeed(36.0)]);
+    assert_eq!(weight, 20.0);
+}
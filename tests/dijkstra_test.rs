@@ -1,8 +1,11 @@
 use perionav::core::{
-    routing::options::{AlgorithmType, RoutingAlgorithmOptions, WeightType},
+    edge::VehicleTypes,
+    routing::dijkstra::DijkstraRoutingAlgorithm,
+    routing::options::{create_vehicle_weight_calculator, create_weight_calculator, AlgorithmType, RoutingAlgorithmOptions, WeightType},
     routing::RoutingResult,
     Graph,
 };
+use perionav::router::Router;
 use rstest::rstest;
 
 use rand::rngs::StdRng;
@@ -19,7 +22,7 @@ fn assert_route_path(result: &Option<RoutingResult>, nodes: Vec<usize>) {
     assert!(!result.is_none());
     let result = result.as_ref().unwrap();
     assert!(!result.paths.is_empty());
-    assert_eq!(result.paths.get(0).unwrap().get_nodes(), nodes);
+    assert_eq!(result.paths.first().unwrap().get_nodes(), nodes);
 }
 
 #[rstest]
@@ -56,7 +59,7 @@ fn test_line_graph(#[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, A
 
     let result = &g.route(&opts, 0, 1);
     assert_route_weight(result, 1.0);
-    assert_route_path(&result, vec![0, 1]);
+    assert_route_path(result, vec![0, 1]);
 }
 
 #[rstest]
@@ -69,6 +72,165 @@ fn test_complex_graph(#[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2
     assert_route_path(&result, vec![3, 1, 4, 0, 6]);
 }
 
+#[rstest]
+fn test_vehicle_types(#[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType) {
+    let g = create_graph::create_vehicles_graph();
+
+    let car_weight = create_vehicle_weight_calculator(&WeightType::TRAVELTIME, VehicleTypes::Car);
+    let opts = RoutingAlgorithmOptions::with_weight_calculator(true, algorithm_type, car_weight);
+    let result = g.route(&opts, 0, 2);
+    assert_route_weight(&result, 1.0);
+    assert_route_path(&result, vec![0, 1, 2]);
+
+    //the bike shortcut is a oneway
+    let bike_weight = create_vehicle_weight_calculator(&WeightType::TRAVELTIME, VehicleTypes::Bike);
+    let opts = RoutingAlgorithmOptions::with_weight_calculator(true, AlgorithmType::DIJKSTRA, bike_weight);
+    let result = g.route(&opts, 0, 2);
+    assert_route_weight(&result, 1.0);
+    assert_route_path(&result, vec![0, 2]);
+    assert_route_weight(&g.route(&opts, 2, 0), 4.0);
+}
+
+//distance and travel time used to be swapped
+#[rstest]
+#[case(WeightType::DISTANCE, 20.0, vec![0, 1, 3])]
+#[case(WeightType::TRAVELTIME, 3.0, vec![0, 2, 3])]
+fn test_weight_types(
+    #[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType,
+    #[case] weight_type: WeightType,
+    #[case] weight: f64,
+    #[case] nodes: Vec<usize>,
+) {
+    let g = create_graph::create_speeds_graph();
+    let opts = RoutingAlgorithmOptions::new(true, algorithm_type, weight_type);
+
+    let result = &g.route(&opts, 0, 3);
+    assert_route_weight(result, weight);
+    assert_route_path(result, nodes);
+}
+
+//the forward and backward speeds of an edge used to be swapped, the backward search of bidirdijkstra follows the edges in reverse
+#[rstest]
+#[case(3, 4, 2.0)]
+#[case(4, 3, 10.0)]
+fn test_directed_speeds(
+    #[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType,
+    #[case] start: usize,
+    #[case] end: usize,
+    #[case] weight: f64,
+) {
+    let g = create_graph::create_speeds_graph();
+    let opts = RoutingAlgorithmOptions::new(true, algorithm_type, WeightType::TRAVELTIME);
+
+    assert_route_weight(&g.route(&opts, start, end), weight);
+}
+
+#[test]
+fn test_wkt() {
+    let g = create_graph::create_coordinates_square_graph();
+    let opts = RoutingAlgorithmOptions::new(true, AlgorithmType::DIJKSTRA, WeightType::DISTANCE);
+
+    let result = g.route(&opts, 0, 1).unwrap();
+    assert_eq!(result.paths[0].get_wkt(&g), "LINESTRING(3.700000 51.000000,3.701000 51.000000)");
+}
+
+#[test]
+fn test_one_to_many() {
+    let g = create_graph::create_complex_graph();
+    let algorithm = DijkstraRoutingAlgorithm {
+        path: true,
+        weight_calculator: create_weight_calculator(&WeightType::DISTANCE),
+    };
+
+    let results = algorithm.route_one_to_many(&g, 3, &[6, 3, 5], f64::INFINITY);
+    assert_route_weight(&results[0], 8.0);
+    assert_route_path(&results[0], vec![3, 1, 4, 0, 6]);
+    assert_route_weight(&results[1], 0.0);
+    assert_route_weight(&results[2], 7.0);
+
+    //5 is further away than the max weight
+    let results = algorithm.route_one_to_many(&g, 3, &[6, 5], 7.5);
+    assert!(results[0].is_none());
+    assert_route_weight(&results[1], 7.0);
+}
+
+#[test]
+fn test_k_closest() {
+    let g = create_graph::create_sub_components_graph();
+    let algorithm = DijkstraRoutingAlgorithm {
+        path: true,
+        weight_calculator: create_weight_calculator(&WeightType::DISTANCE),
+    };
+
+    //0 can reach 1 and 2 but not 6
+    let hits = algorithm.route_k_closest(&g, 0, &[6, 2, 1], 2, f64::INFINITY);
+    assert_eq!(hits.iter().map(|(i, _)| *i).collect::<Vec<usize>>(), vec![2, 1]);
+    assert_eq!(hits[0].1.weight, 1.0);
+    assert_eq!(hits[1].1.weight, 2.0);
+    assert_eq!(hits[1].1.paths[0].get_nodes(), vec![0, 1, 2]);
+
+    let hits = algorithm.route_k_closest(&g, 0, &[6, 2, 1], 5, f64::INFINITY);
+    assert_eq!(hits.len(), 2);
+
+    //1 and 2 are both at weight 1 from 3, 6 is further away than the max weight
+    let hits = algorithm.route_k_closest(&g, 3, &[6, 2, 1], 3, 1.0);
+    let mut indexes: Vec<usize> = hits.iter().map(|(i, _)| *i).collect();
+    indexes.sort();
+    assert_eq!(indexes, vec![1, 2]);
+}
+
+#[test]
+fn test_router_k_closest() {
+    let router = Router::new(create_graph::create_coordinates_grid_graph());
+    let location = |node: usize| (51.0 + 0.0009 * (node / 3) as f64, 3.7 + 0.0014 * (node % 3) as f64);
+
+    //the targets are the locations of nodes 8, 1 and 4, the hits are the indexes in the targets
+    let hits = router.route_k_closest(location(0), &[location(8), location(1), location(4)], 2);
+    assert_eq!(hits.iter().map(|(i, _)| *i).collect::<Vec<usize>>(), vec![1, 2]);
+    assert_eq!(hits[1].1.paths[0].get_nodes().last(), Some(&4));
+}
+
+#[test]
+fn test_many_to_one() {
+    let g = create_graph::create_sub_components_graph();
+    let algorithm = DijkstraRoutingAlgorithm {
+        path: true,
+        weight_calculator: create_weight_calculator(&WeightType::TRAVELTIME),
+    };
+
+    let results = algorithm.route_many_to_one(&g, &[7, 0, 6], 2, f64::INFINITY);
+    assert_route_weight(&results[0], 3.0);
+    assert_route_weight(&results[1], 2.0);
+    assert_route_path(&results[1], vec![0, 1, 2]);
+    assert_route_weight(&results[2], 2.0);
+    assert_route_path(&results[2], vec![6, 5, 2]);
+
+    //2 can't reach 7 because of the one way edges
+    let results = algorithm.route_many_to_one(&g, &[2], 7, f64::INFINITY);
+    assert!(results[0].is_none());
+}
+
+#[test]
+fn test_all_to_one_equals_forward_routes() {
+    let nodes = 300;
+    let g = create_graph::create_random_graph(nodes, 900);
+    let opts = RoutingAlgorithmOptions::new(true, AlgorithmType::DIJKSTRA, WeightType::TRAVELTIME);
+    let algorithm = DijkstraRoutingAlgorithm {
+        path: false,
+        weight_calculator: create_weight_calculator(&WeightType::TRAVELTIME),
+    };
+
+    let end = 42;
+    let weights = algorithm.route_all_to_one(&g, end, f64::INFINITY);
+    for start in 0..nodes {
+        let result = g.route(&opts, start, end);
+        match weights.get(&start) {
+            None => assert!(result.is_none(), "{} should not reach {}", start, end),
+            Some(weight) => assert!(delta_equal(result.unwrap().weight, *weight, 1E-7), "different weight from {}", start),
+        }
+    }
+}
+
 #[test]
 fn test_different_algorithms_equal() {
     let nodes = 1000;
@@ -101,7 +263,7 @@ fn result_equal(r1: &Option<RoutingResult>, r2: &Option<RoutingResult>) -> bool
     let result1 = r1.as_ref().unwrap();
     let result2 = r2.as_ref().unwrap();
 
-    return delta_equal(result1.weight, result2.weight, 1E-7);
+    delta_equal(result1.weight, result2.weight, 1E-7)
 }
 
 fn delta_equal(f1: f64, f2: f64, delta: f64) -> bool {
@@ -0,0 +1,63 @@
+use perionav::core::matching::{match_trace, GpsPoint, MapMatchingOptions};
+use perionav::core::spatialindex::{EdgeIndex, DEFAULT_CELL_SIZE};
+use perionav::reader::gps_reader::{read_csv, read_gpx};
+
+mod create_graph;
+
+fn point(lat: f64, lon: f64) -> GpsPoint {
+    GpsPoint { lat, lon, time: None }
+}
+
+#[test]
+fn test_match_trace() {
+    let g = create_graph::create_coordinates_grid_graph();
+    let index = EdgeIndex::new(&g, DEFAULT_CELL_SIZE);
+
+    //drive east along the southern row and then north along the eastern column, with some noise
+    let points = vec![
+        point(51.00005, 3.7003),
+        point(50.99995, 3.7012),
+        point(51.00004, 3.7021),
+        point(51.00002, 3.7027),
+        point(51.0004, 3.70285),
+        point(51.0011, 3.70275),
+        point(51.0017, 3.70282),
+    ];
+
+    let result = match_trace(&g, &index, &points, &MapMatchingOptions::default());
+
+    assert_eq!(result.paths.len(), 1);
+    assert_eq!(result.paths[0].get_nodes(), vec![0, 1, 2, 5, 8]);
+}
+
+#[test]
+fn test_match_trace_ignores_outliers() {
+    let g = create_graph::create_coordinates_grid_graph();
+    let index = EdgeIndex::new(&g, DEFAULT_CELL_SIZE);
+
+    //the third point is far away from any road
+    let points = vec![
+        point(51.00003, 3.7002),
+        point(51.00003, 3.7011),
+        point(51.01, 3.75),
+        point(51.00002, 3.7025),
+    ];
+
+    let result = match_trace(&g, &index, &points, &MapMatchingOptions::default());
+
+    assert_eq!(result.paths.len(), 1);
+    assert_eq!(result.paths[0].get_nodes(), vec![0, 1, 2]);
+}
+
+#[test]
+fn test_read_gps_files() {
+    let points = read_gpx("tests/data/trace.gpx").unwrap();
+    assert_eq!(points.len(), 2);
+    assert_eq!((points[0].lat, points[0].lon, points[0].time), (51.0, 3.7, Some(1709281800.0)));
+    assert_eq!((points[1].lat, points[1].lon, points[1].time), (51.1, 3.8, None));
+
+    let points = read_csv("tests/data/trace.csv").unwrap();
+    assert_eq!(points.len(), 2);
+    assert_eq!((points[0].lat, points[0].lon, points[0].time), (51.0, 3.7, Some(1709281800.0)));
+    assert_eq!(points[1].time, None);
+}
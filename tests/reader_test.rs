@@ -0,0 +1,273 @@
+use std::collections::{HashMap, HashSet};
+use std::io::ErrorKind;
+
+use osmpbf::{Element, ElementReader};
+use perionav::core::edge::VehicleTypes;
+use perionav::core::routing::options::{AlgorithmType, RoutingAlgorithmOptions, WeightType};
+use perionav::core::Graph;
+use perionav::reader::boundary::{read_poly, Boundary};
+use perionav::reader::edge_list_reader::{read_csv_graph, read_dimacs_graph, read_geojson_graph};
+use perionav::reader::osm_reader::{IdMapping, OsmReader, ReaderOptions};
+use perionav::reader::profile::Profile;
+use rstest::rstest;
+
+//(osm id, lat, lon) of every node and (osm id, osm id) of every directed edge, sorted so that graphs can be compared
+type Summary = (Vec<(i64, i64, i64)>, Vec<(i64, i64)>);
+
+fn summarize(g: &impl Graph) -> Summary {
+    let osm_id = |node: usize| g.get_node(node).unwrap().osm_id;
+
+    let mut nodes = vec![];
+    let mut edges = vec![];
+    for node in 0..g.get_nr_nodes() {
+        let n = g.get_node(node).unwrap();
+        nodes.push((n.osm_id, (n.lat * 1E7).round() as i64, (n.lon * 1E7).round() as i64));
+        g.do_for_all_neighbors(node, false, |adj_node| edges.push((osm_id(node), osm_id(adj_node))));
+    }
+    nodes.sort();
+    edges.sort();
+
+    (nodes, edges)
+}
+
+fn read(file_name: &str, id_mapping: IdMapping) -> impl Graph {
+    read_inside(file_name, id_mapping, None)
+}
+
+fn read_inside(file_name: &str, id_mapping: IdMapping, boundary: Option<Boundary>) -> impl Graph {
+    let options = ReaderOptions { id_mapping, boundary };
+    let reader = OsmReader::with_options(file_name, vec![Profile::car()], options);
+    reader.unwrap().read_graph().unwrap()
+}
+
+#[rstest]
+fn test_formats(
+    #[values(
+        "tests/data/small_network.osm",
+        "tests/data/small_network.osm.bz2",
+        "tests/data/small_network_nodes.osm.pbf"
+    )]
+    file_name: &str,
+    #[values(IdMapping::SortedArrays, IdMapping::Bitsets)] id_mapping: IdMapping,
+) {
+    let expected = read("tests/data/small_network.osm.pbf", IdMapping::SortedArrays);
+    let g = read(file_name, id_mapping);
+
+    assert_eq!(g.get_nr_nodes(), expected.get_nr_nodes());
+    assert_eq!(g.get_nr_edges(), expected.get_nr_edges());
+    assert_eq!(summarize(&g), summarize(&expected));
+}
+
+#[test]
+fn test_josm_file() {
+    let g = read("tests/data/small_network.osm", IdMapping::SortedArrays);
+    let (nodes, edges) = summarize(&g);
+
+    //the new ferry terminal has a negative id, the deleted way and its node are left out
+    assert!(nodes.iter().any(|(osm_id, _, _)| *osm_id == -101));
+    assert!(!nodes.iter().any(|(osm_id, _, _)| *osm_id == 11));
+    assert!(edges.contains(&(8, -101)));
+
+    //the primary road is oneway
+    assert!(edges.contains(&(2, 7)));
+    assert!(!edges.contains(&(7, 2)));
+}
+
+#[derive(PartialEq)]
+enum NodeType {
+    Shape,
+    Tower,
+    Barrier,
+}
+
+//the reader before the blobs were decoded in parallel: for_each over the file and a map with the type of every node of the ways
+//only the ways that are in the graph are used, the result is (osm id of every graph node, undirected edges as (osm id, osm id))
+fn read_sequential(file_name: &str, way_ids: &HashSet<i64>) -> (Vec<i64>, Vec<(i64, i64)>) {
+    let mut ways = vec![];
+    let mut barriers = vec![];
+    let reader = ElementReader::from_path(file_name).unwrap();
+    reader
+        .for_each(|element| match element {
+            Element::Way(way) if way_ids.contains(&way.id()) => ways.push(way.refs().collect::<Vec<i64>>()),
+            Element::Node(node) if node.tags().any(|(key, _)| key == "barrier") => barriers.push(node.id()),
+            Element::DenseNode(node) if node.tags().any(|(key, _)| key == "barrier") => barriers.push(node.id()),
+            _ => {}
+        })
+        .unwrap();
+
+    let mut node_types = HashMap::new();
+    for refs in ways.iter() {
+        for (position, node_id) in refs.iter().enumerate() {
+            if position == 0 || position == refs.len() - 1 {
+                node_types.insert(*node_id, NodeType::Tower);
+            } else {
+                node_types
+                    .entry(*node_id)
+                    .and_modify(|node_type| *node_type = NodeType::Tower)
+                    .or_insert(NodeType::Shape);
+            }
+        }
+    }
+    for node_id in barriers {
+        if let Some(node_type @ NodeType::Shape) = node_types.get_mut(&node_id) {
+            *node_type = NodeType::Barrier;
+        }
+    }
+
+    let mut nodes = vec![];
+    for (node_id, node_type) in node_types.iter() {
+        match node_type {
+            NodeType::Shape => {}
+            NodeType::Tower => nodes.push(*node_id),
+            NodeType::Barrier => nodes.extend([*node_id, *node_id]),
+        }
+    }
+    nodes.sort();
+
+    let mut edges = vec![];
+    for refs in ways.iter() {
+        let mut last = None;
+        for node_id in refs.iter().filter(|node_id| node_types[node_id] != NodeType::Shape) {
+            if let Some(last) = last {
+                edges.push((last, *node_id));
+            }
+            if node_types[node_id] == NodeType::Barrier {
+                edges.push((*node_id, *node_id));
+            }
+            last = Some(*node_id);
+        }
+    }
+
+    (nodes, normalize(edges))
+}
+
+fn normalize(edges: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+    let mut edges: Vec<(i64, i64)> = edges.into_iter().map(|(a, b)| (a.min(b), a.max(b))).collect();
+    edges.sort();
+    edges.dedup();
+    edges
+}
+
+#[rstest]
+fn test_same_as_sequential_reader(
+    #[values(
+        "tests/data/small_network.osm.pbf",
+        "tests/data/small_network_nodes.osm.pbf",
+        "tests/data/grid_network.osm.pbf"
+    )]
+    file_name: &str,
+    #[values(IdMapping::SortedArrays, IdMapping::Bitsets)] id_mapping: IdMapping,
+) {
+    //bikes pass every barrier, so that the edges of the barriers stay in the graph
+    let bike = Profile {
+        vehicle_type: VehicleTypes::Bike,
+        blocked_barriers: HashSet::new(),
+        ..Profile::car()
+    };
+    let options = ReaderOptions { id_mapping, boundary: None };
+    let reader = OsmReader::with_options(file_name, vec![Profile::car(), bike], options).unwrap();
+    let g = reader.read_graph().unwrap();
+    let osm_id = |node: usize| g.get_node(node).unwrap().osm_id;
+
+    let mut nodes: Vec<i64> = (0..g.get_nr_nodes()).map(osm_id).collect();
+    nodes.sort();
+    let mut edges = vec![];
+    let mut way_ids = HashSet::new();
+    for node in 0..g.get_nr_nodes() {
+        g.do_for_all_neighbors(node, false, |adj_node| {
+            edges.push((osm_id(node), osm_id(adj_node)));
+            let info = g.get_directed_vehicle_specific_edge_information(node, adj_node, false, VehicleTypes::Bike);
+            way_ids.extend(info.and_then(|info| info.get_attributes().way_id));
+        });
+    }
+
+    assert!(way_ids.len() > 1);
+    assert_eq!((nodes, normalize(edges)), read_sequential(file_name, &way_ids));
+}
+
+#[rstest]
+fn test_clipping(#[values(IdMapping::SortedArrays, IdMapping::Bitsets)] id_mapping: IdMapping, #[values(true, false)] poly: bool) {
+    let boundary = if poly {
+        read_poly("tests/data/small_network.poly").unwrap()
+    } else {
+        Boundary::from_bbox(50.9, 3.69, 51.0015, 3.7015)
+    };
+    let g = read_inside("tests/data/small_network.osm.pbf", id_mapping, Some(boundary));
+    let (nodes, edges) = summarize(&g);
+
+    //only 1 and 2 are inside, the ways continue up to the first node outside, so the bollard at 3 is the end of its way now
+    //and cars can't enter it from 2
+    let node_ids: Vec<i64> = nodes.iter().map(|(osm_id, _, _)| *osm_id).collect();
+    assert_eq!(node_ids, vec![1, 2, 3, 6]);
+    assert_eq!(edges, vec![(1, 2), (2, 1), (2, 6), (3, 2)]);
+}
+
+fn find_node(g: &impl Graph, lat: f64, lon: f64) -> usize {
+    (0..g.get_nr_nodes())
+        .find(|node| {
+            let n = g.get_node(*node).unwrap();
+            (n.lat - lat).abs() < 1E-9 && (n.lon - lon).abs() < 1E-9
+        })
+        .unwrap()
+}
+
+//every format has a square with a diagonal from the south west to the north east corner, the north side is oneway to the west
+fn check_square(g: impl Graph) {
+    assert_eq!(g.get_nr_nodes(), 4);
+    assert_eq!(g.get_nr_edges(), 9);
+
+    let south_west = find_node(&g, 51.0, 3.7);
+    let north_west = find_node(&g, 51.01, 3.7);
+    let north_east = find_node(&g, 51.01, 3.71);
+
+    //against the oneway the diagonal is the shortest
+    let opts = RoutingAlgorithmOptions::new(true, AlgorithmType::DIJKSTRA, WeightType::DISTANCE);
+    let result = g.route(&opts, north_west, north_east).unwrap();
+    assert_eq!(result.paths[0].get_nodes(), vec![north_west, south_west, north_east]);
+
+    let result = g.route(&opts, north_east, north_west).unwrap();
+    assert_eq!(result.paths[0].get_nodes(), vec![north_east, north_west]);
+    assert!((result.distance - 708.4).abs() < 1.0);
+}
+
+#[rstest]
+fn test_edge_lists(#[values("csv", "dimacs", "geojson")] format: &str) {
+    match format {
+        "csv" => check_square(read_csv_graph("tests/data/square.csv").unwrap()),
+        "dimacs" => check_square(read_dimacs_graph("tests/data/square.gr", Some("tests/data/square.co")).unwrap()),
+        _ => check_square(read_geojson_graph("tests/data/square.geojson").unwrap()),
+    }
+}
+
+#[test]
+fn test_invalid_edge_lists() {
+    assert!(read_csv_graph("tests/data/square.geojson").is_err());
+    assert!(read_dimacs_graph("tests/data/square.co", None).is_err());
+    assert!(read_geojson_graph("tests/data/square.csv").is_err());
+}
+
+#[rstest]
+fn test_id_mappings(#[values("tests/data/small_network.osm.pbf", "tests/data/grid_network.osm.pbf")] file_name: &str) {
+    let expected = read(file_name, IdMapping::SortedArrays);
+    let g = read(file_name, IdMapping::Bitsets);
+
+    assert_eq!(g.get_nr_edges(), expected.get_nr_edges());
+    assert_eq!(summarize(&g), summarize(&expected));
+}
+
+#[test]
+fn test_large_node_id() {
+    let options = |id_mapping| ReaderOptions { id_mapping, boundary: None };
+    let g = OsmReader::with_options("tests/data/large_node_id.osm", vec![Profile::car()], options(IdMapping::SortedArrays));
+    assert_eq!(g.unwrap().read_graph().unwrap().get_nr_nodes(), 2);
+
+    //one bit per possible id only goes up to 2^40
+    let result = OsmReader::with_options("tests/data/large_node_id.osm", vec![Profile::car()], options(IdMapping::Bitsets));
+    assert!(result.is_err_and(|e| e.kind() == ErrorKind::InvalidData));
+}
+
+#[test]
+fn test_invalid_file() {
+    assert!(OsmReader::new("tests/data/missing.osm").is_err());
+    assert!(OsmReader::new("tests/data/missing.osm.pbf").is_err());
+}
@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+
+use perionav::core::spatialindex::{EdgeIndex, DEFAULT_CELL_SIZE};
+use perionav::core::Graph;
+use rstest::rstest;
+
+mod create_graph;
+
+#[rstest]
+fn test_nearest(#[values(DEFAULT_CELL_SIZE, 0.0001, 1.0)] cell_size: f64) {
+    let g = create_graph::create_coordinates_grid_graph();
+    let index = EdgeIndex::new(&g, cell_size);
+    assert_eq!(index.get_nr_edges(), 12);
+
+    //just north of the middle of the edge between 0 and 1
+    let hits = index.nearest(51.0001, 3.7007, 2);
+    assert_eq!(hits.len(), 2);
+    assert_eq!((hits[0].node1, hits[0].node2), (0, 1));
+    assert!((hits[0].projection.fraction - 0.5).abs() < 1e-6);
+    assert!((hits[0].projection.distance - 11.1).abs() < 0.1, "distance {}", hits[0].projection.distance);
+    assert!(hits[1].projection.distance > hits[0].projection.distance);
+
+    //far outside of the graph
+    let hits = index.nearest(51.0013, 3.8, 1);
+    assert_eq!(hits.len(), 1);
+    assert_eq!((hits[0].node1, hits[0].node2), (5, 8));
+}
+
+#[test]
+fn test_within_radius() {
+    let g = create_graph::create_coordinates_grid_graph();
+    let index = EdgeIndex::new(&g, DEFAULT_CELL_SIZE);
+
+    //node 4 is in the middle of the grid and has 4 edges
+    let node = g.get_node(4).unwrap();
+    let hits = index.within_radius(node.lat, node.lon, 10.0);
+    let edges: HashSet<(usize, usize)> = hits.iter().map(|h| (h.node1, h.node2)).collect();
+    assert_eq!(edges, HashSet::from([(1, 4), (3, 4), (4, 5), (4, 7)]));
+
+    assert!(index.within_radius(51.1, 3.8, 100.0).is_empty());
+}
+
+#[test]
+fn test_in_bbox() {
+    let g = create_graph::create_coordinates_grid_graph();
+    let index = EdgeIndex::new(&g, DEFAULT_CELL_SIZE);
+
+    //a small box on the edge between 0 and 1, without containing any node
+    let edges = index.in_bbox(50.9999, 3.7005, 51.0001, 3.7009);
+    assert_eq!(edges, vec![(0, 1)]);
+
+    let edges = index.in_bbox(50.0, 3.0, 52.0, 4.0);
+    assert_eq!(edges.len(), 12);
+}
+
+#[test]
+fn test_rebuild_and_serialize() {
+    let mut g = create_graph::create_coordinates_grid_graph();
+    let mut index = EdgeIndex::new(&g, DEFAULT_CELL_SIZE);
+
+    g.keep_nodes(&HashSet::from([0, 1, 3, 4]));
+    index.rebuild(&g);
+    assert_eq!(index.get_nr_edges(), 4);
+
+    let mut buf = vec![];
+    index.write_to(&mut buf).unwrap();
+    let read_index = EdgeIndex::read_from(&mut buf.as_slice()).unwrap();
+
+    assert_eq!(read_index.get_nr_edges(), 4);
+    let hits = read_index.nearest(51.0001, 3.7007, 1);
+    assert_eq!((hits[0].node1, hits[0].node2), (0, 1));
+
+    assert!(EdgeIndex::read_from(&mut &b"not an index"[..]).is_err());
+}
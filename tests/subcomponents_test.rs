@@ -1,9 +1,14 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use perionav::core::{
+    components::biconnected::Bridge,
+    components::filter::{get_kept_nodes, ComponentsPolicy},
     components::options::{AlgorithmType, ComponentsAlgorithmOptions},
+    components::vehicle::{get_vehicle_components, VehicleNeighbors},
+    edge::VehicleTypes,
     Graph,
 };
+use perionav::router::Router;
 use rstest::rstest;
 
 mod create_graph;
@@ -126,3 +131,166 @@ fn test_algorithms_equal() {
     assert_components_equal(&components, &components2);
     assert_components_equal(&components2, &components3);
 }
+
+#[test]
+fn test_weakly_connected_components() {
+    assert_component_sizes(create_graph::create_village_graph().get_weakly_connected_subgraphs(), vec![2, 8]);
+    assert_component_sizes(create_graph::create_strongly_disconnected_graph().get_weakly_connected_subgraphs(), vec![3]);
+    assert_component_sizes(create_graph::create_ii_graph().get_weakly_connected_subgraphs(), vec![2, 2]);
+    assert_component_sizes(create_graph::create_connected_ii_graph().get_weakly_connected_subgraphs(), vec![4]);
+}
+
+#[test]
+fn test_bridges() {
+    let result = create_graph::create_village_graph().get_biconnected_components();
+
+    //closing the road to the village cuts off 4 nodes, the oneway between 8 and 9 is a bridge too
+    let bridges = vec![
+        Bridge {
+            nodes: (2, 4),
+            nodes_cut_off: 4,
+        },
+        Bridge {
+            nodes: (5, 7),
+            nodes_cut_off: 1,
+        },
+        Bridge {
+            nodes: (8, 9),
+            nodes_cut_off: 1,
+        },
+    ];
+    assert_eq!(result.bridges, bridges);
+    assert_eq!(result.articulation_points, HashSet::from([2, 4, 5]));
+
+    let mut components: Vec<Vec<usize>> = result
+        .components
+        .iter()
+        .map(|c| {
+            let mut c: Vec<usize> = c.iter().copied().collect();
+            c.sort_unstable();
+            c
+        })
+        .collect();
+    components.sort();
+    assert_eq!(components, vec![vec![0, 1, 2, 3], vec![2, 4], vec![4, 5, 6], vec![5, 7], vec![8, 9]]);
+}
+
+#[test]
+fn test_no_bridges() {
+    let result = create_graph::create_square_graph().get_biconnected_components();
+    assert!(result.bridges.is_empty());
+    assert!(result.articulation_points.is_empty());
+    assert_component_sizes(result.components, vec![4]);
+
+    //without the directions the legs graph is a triangle
+    let result = create_graph::create_legs_graph().get_biconnected_components();
+    assert!(result.bridges.is_empty());
+    assert_component_sizes(result.components, vec![3]);
+}
+
+#[test]
+fn test_bridges_of_random_graph() {
+    //removing a bridge splits its weakly connected component, removing any other edge doesn't
+    let g = create_graph::create_random_graph(300, 330);
+    let result = g.get_biconnected_components();
+    let nr_components = g.get_weakly_connected_subgraphs().len();
+    assert!(!result.bridges.is_empty());
+
+    let bridges: HashSet<(usize, usize)> = result.bridges.iter().map(|b| b.nodes).collect();
+    let mut edges = HashSet::new();
+    for node in 0..g.get_nr_nodes() {
+        g.do_for_all_neighbors(node, false, |adj_node| {
+            edges.insert((node.min(adj_node), node.max(adj_node)));
+        });
+    }
+
+    for (from, to) in edges {
+        let mut without = create_graph::create_random_graph(300, 330);
+        without.remove_edge(from, to);
+        let split = without.get_weakly_connected_subgraphs().len() > nr_components;
+        assert_eq!(split, bridges.contains(&(from, to)), "edge ({}, {})", from, to);
+    }
+}
+
+#[rstest]
+#[case(ComponentsPolicy::Largest, vec![0, 1, 2, 3])]
+#[case(ComponentsPolicy::MinSize(3), vec![0, 1, 2, 3, 5, 6, 7])]
+#[case(ComponentsPolicy::MinSize(1), (0..10).collect())]
+#[case(ComponentsPolicy::LargestPerIsland, vec![0, 1, 2, 3, 5, 6, 7, 8, 9])]
+fn test_components_policies(#[case] policy: ComponentsPolicy, #[case] expected: Vec<usize>) {
+    let g = create_graph::create_coordinates_islands_graph();
+    let opts = ComponentsAlgorithmOptions::new(AlgorithmType::TARJAN);
+
+    let mut kept_nodes: Vec<usize> = get_kept_nodes(&g, g.get_strongly_connected_subgraphs(&opts), policy)
+        .into_iter()
+        .collect();
+    kept_nodes.sort_unstable();
+    assert_eq!(kept_nodes, expected);
+}
+
+#[test]
+fn test_marked_nodes() {
+    let g = create_graph::create_coordinates_islands_graph();
+    let opts = ComponentsAlgorithmOptions::new(AlgorithmType::TARJAN);
+    let kept_nodes = get_kept_nodes(&g, g.get_strongly_connected_subgraphs(&opts), ComponentsPolicy::LargestPerIsland);
+    let router = Router::with_routable_nodes(g, &kept_nodes);
+
+    //the dead end at 4 is still in the graph, but the start snaps to 3 instead
+    let result = router.route((51.0021, 3.7), (51.0, 3.701)).unwrap();
+    assert_eq!(result.paths[0].get_nodes().first(), Some(&3));
+    assert_eq!(result.paths[0].get_nodes().last(), Some(&1));
+
+    //routing on the island works, but not from the island to the mainland
+    assert!(router.route((51.01, 3.7), (51.011, 3.7005)).is_some());
+    assert!(router.route((51.01, 3.7), (51.0, 3.701)).is_none());
+}
+
+#[rstest]
+#[case(VehicleTypes::Car, vec![0, 1, 2, 3])]
+#[case(VehicleTypes::Bike, vec![0, 1, 2, 3, 4, 5])]
+fn test_vehicle_components(#[case] vehicle_type: VehicleTypes, #[case] expected: Vec<usize>) {
+    let g = create_graph::create_coordinates_cycleway_graph();
+    let components = get_vehicle_components(&g, vehicle_type, &AlgorithmType::TARJAN);
+
+    let mut kept_nodes: Vec<usize> = get_kept_nodes(&VehicleNeighbors::new(&g, vehicle_type), components, ComponentsPolicy::Largest)
+        .into_iter()
+        .collect();
+    kept_nodes.sort_unstable();
+    assert_eq!(kept_nodes, expected);
+}
+
+#[test]
+fn test_vehicle_networks() {
+    let g = create_graph::create_coordinates_cycleway_graph();
+    let networks = [VehicleTypes::Car, VehicleTypes::Bike]
+        .into_iter()
+        .map(|vehicle_type| {
+            let components = get_vehicle_components(&g, vehicle_type, &AlgorithmType::TARJAN);
+            (vehicle_type, get_kept_nodes(&VehicleNeighbors::new(&g, vehicle_type), components, ComponentsPolicy::Largest))
+        })
+        .collect();
+    let router = Router::with_vehicle_networks(g, networks);
+
+    assert!(router.is_in_main_network(4, VehicleTypes::Bike));
+    assert!(!router.is_in_main_network(4, VehicleTypes::Car));
+    assert!(!router.is_in_main_network(4, VehicleTypes::Hgv));
+
+    //cars snap to 3 instead of the cycleway
+    let result = router.route((51.003, 3.7), (51.0, 3.701)).unwrap();
+    assert_eq!(result.paths[0].get_nodes().first(), Some(&3));
+    assert_eq!(result.paths[0].get_nodes().last(), Some(&1));
+}
+
+#[test]
+fn test_empty_vehicle_network() {
+    let g = create_graph::create_coordinates_cycleway_graph();
+    let components = get_vehicle_components(&g, VehicleTypes::Bike, &AlgorithmType::TARJAN);
+    let bike_network = get_kept_nodes(&VehicleNeighbors::new(&g, VehicleTypes::Bike), components, ComponentsPolicy::Largest);
+    let networks = HashMap::from([(VehicleTypes::Car, HashSet::new()), (VehicleTypes::Bike, bike_network)]);
+    let router = Router::with_vehicle_networks(g, networks);
+
+    //there is no car edge to snap to
+    assert!(router.route((51.003, 3.7), (51.0, 3.701)).is_none());
+    assert!(router.route_many_to_one(&[(51.003, 3.7)], (51.0, 3.701)).iter().all(|r| r.is_none()));
+    assert!(router.route_k_closest((51.003, 3.7), &[(51.0, 3.701)], 1).is_empty());
+}
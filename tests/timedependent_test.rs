@@ -0,0 +1,149 @@
+use std::io::ErrorKind;
+use std::rc::Rc;
+
+use perionav::core::edge::VehicleTypes;
+use perionav::core::routing::options::{create_vehicle_weight_calculator, RoutingAlgorithmOptions, WeightType};
+use perionav::core::routing::tddijkstra::TimeDependentDijkstraRoutingAlgorithm;
+use perionav::core::traffic::{EdgeLookup, SpeedProfile, SpeedProfiles, TrafficKey};
+use perionav::core::Graph;
+use perionav::reader::traffic_reader::read_speed_profiles;
+use rstest::rstest;
+
+mod create_graph;
+
+const MONDAY: f64 = 1709510400.0; //2024-03-04T00:00:00Z
+const HOUR: f64 = 3600.0;
+
+//3.6 km/h (1 m/s) on monday between 07:00 and 09:00, 108 km/h (30 m/s) the rest of the week
+fn create_rush_hour_profile() -> SpeedProfile {
+    let speeds = (0..672).map(|slot| if (28..36).contains(&slot) { 3.6 } else { 108.0 }).collect();
+    SpeedProfile::new(speeds).unwrap()
+}
+
+fn route(speed_profiles: SpeedProfiles, departure: f64) -> (f64, Vec<usize>) {
+    let g = create_graph::create_road_classes_graph();
+    let opts = RoutingAlgorithmOptions {
+        routing_algorithm: Box::new(TimeDependentDijkstraRoutingAlgorithm {
+            path: true,
+            weight_calculator: create_vehicle_weight_calculator(&WeightType::TRAVELTIME, VehicleTypes::Car),
+            speed_profiles: Rc::new(speed_profiles),
+            departure,
+            utc_offset: 0.0,
+        }),
+    };
+
+    let result = g.route(&opts, 0, 3).unwrap();
+    (result.weight, result.paths[0].get_nodes())
+}
+
+#[test]
+fn test_profile_length() {
+    assert!(SpeedProfile::new(vec![50.0; 96]).is_some());
+    assert!(SpeedProfile::new(vec![50.0; 672]).is_some());
+    assert!(SpeedProfile::new(vec![50.0; 95]).is_none());
+    assert!(SpeedProfile::new(vec![-1.0; 96]).is_none());
+}
+
+//a traversal can take longer than the period of the profile
+#[test]
+fn test_slow_long_edge() {
+    let profile = SpeedProfile::new(vec![3.6; 96]).unwrap();
+    assert!((profile.travel_time(100_000.0, MONDAY + 0.5 * HOUR) - 100_000.0).abs() < 1E-6);
+
+    //only the first hour of the day at 1 m/s, 3600m per day
+    let speeds = (0..96).map(|slot| if slot < 4 { 3.6 } else { 0.0 }).collect();
+    let profile = SpeedProfile::new(speeds).unwrap();
+    assert!((profile.travel_time(9000.0, MONDAY) - (48.0 * HOUR + 1800.0)).abs() < 1E-6);
+
+    assert_eq!(SpeedProfile::new(vec![0.0; 96]).unwrap().travel_time(1.0, MONDAY), f64::INFINITY);
+}
+
+#[test]
+fn test_weekly_slots() {
+    let profile = create_rush_hour_profile();
+    assert_eq!(profile.get_speed(MONDAY + 8.0 * HOUR), 1.0);
+    assert_eq!(profile.get_speed(MONDAY + 9.0 * HOUR), 30.0);
+
+    //one week later is the same slot, one day later isn't
+    assert_eq!(profile.get_speed(MONDAY + 7.0 * 24.0 * HOUR + 8.0 * HOUR), 1.0);
+    assert_eq!(profile.get_speed(MONDAY + 32.0 * HOUR), 30.0);
+}
+
+#[test]
+fn test_travel_time_fifo() {
+    //1 m/s in the first slot of the day, 10 m/s in the second one and 0 (closed) in the third one
+    let mut speeds = vec![36.0; 96];
+    speeds[0] = 3.6;
+    speeds[2] = 0.0;
+    let profile = SpeedProfile::new(speeds).unwrap();
+
+    //10m at 1 m/s, then 90m at 10 m/s
+    assert!((profile.travel_time(100.0, MONDAY + 890.0) - 19.0).abs() < 1E-6);
+    assert!((profile.travel_time(100.0, MONDAY + 900.0) - 10.0).abs() < 1E-6);
+
+    //leaving later never means arriving earlier
+    let mut last_arrival = f64::NEG_INFINITY;
+    for departure in (0..3000).map(|s| MONDAY + s as f64) {
+        let arrival = departure + profile.travel_time(100.0, departure);
+        assert!(arrival >= last_arrival);
+        last_arrival = arrival;
+    }
+
+    //the road is closed in the third slot, so the car waits until it opens again
+    assert!((profile.travel_time(100.0, MONDAY + 1800.0) - 910.0).abs() < 1E-6);
+    assert_eq!(SpeedProfile::new(vec![0.0; 96]).unwrap().travel_time(1.0, MONDAY), f64::INFINITY);
+}
+
+#[test]
+fn test_departure_time() {
+    let g = create_graph::create_road_classes_graph();
+    let mut speed_profiles = SpeedProfiles::new();
+    speed_profiles
+        .add(TrafficKey::Way(1), Rc::new(create_rush_hour_profile()), &EdgeLookup::new(&g))
+        .unwrap();
+
+    //during rush hour the motorway takes 20s and the residential road 2.4s
+    let (weight, nodes) = route(speed_profiles.clone(), MONDAY + 8.0 * HOUR);
+    assert!((weight - 2.4).abs() < 1E-6);
+    assert_eq!(nodes, vec![0, 2, 3]);
+
+    let (weight, nodes) = route(speed_profiles.clone(), MONDAY + 12.0 * HOUR);
+    assert!((weight - 20.0 / 30.0).abs() < 1E-6);
+    assert_eq!(nodes, vec![0, 1, 3]);
+
+    //rush hour starts on the second edge: 8.75m of it at 30 m/s and the last 1.25m at 1 m/s
+    let (weight, nodes) = route(speed_profiles, MONDAY + 7.0 * HOUR - 0.625);
+    assert!((weight - 1.875).abs() < 1E-4); //times around 2024 in seconds are only precise to about 1E-7
+    assert_eq!(nodes, vec![0, 1, 3]);
+}
+
+#[test]
+fn test_read_speed_profiles() {
+    let g = create_graph::create_road_classes_graph();
+    let lookup = EdgeLookup::new(&g);
+    let mut speed_profiles = SpeedProfiles::new();
+    for (key, profile) in read_speed_profiles("tests/data/speed_profiles.csv").unwrap() {
+        speed_profiles.add(key, profile, &lookup).unwrap();
+    }
+
+    //the edge profile is for osm nodes 13 and 12, indices 3 and 2
+    assert!(speed_profiles.get_profile(Some(1), 0, 1).is_some());
+    assert!(speed_profiles.get_profile(None, 2, 3).is_some());
+    assert!(speed_profiles.get_profile(Some(2), 0, 2).is_none());
+}
+
+#[test]
+fn test_unknown_edge_profile() {
+    let g = create_graph::create_road_classes_graph();
+    let result = SpeedProfiles::new().add(TrafficKey::Edge(10, 13), Rc::new(create_rush_hour_profile()), &EdgeLookup::new(&g));
+    assert!(result.is_err_and(|e| e.kind() == ErrorKind::InvalidData));
+}
+
+#[rstest]
+#[case("tests/data/speed_profiles_too_short.csv")]
+//only the header of speed profiles can be skipped
+#[case("tests/data/speed_profiles_invalid_first_line.csv")]
+fn test_read_invalid_speed_profiles(#[case] file_name: &str) {
+    let result = read_speed_profiles(file_name);
+    assert!(result.is_err_and(|e| e.kind() == ErrorKind::InvalidData));
+}
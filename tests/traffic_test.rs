@@ -0,0 +1,144 @@
+use std::io::ErrorKind;
+use std::rc::Rc;
+
+use perionav::core::edge::VehicleTypes;
+use perionav::core::routing::options::{create_traffic_weight_calculator, AlgorithmType, RoutingAlgorithmOptions};
+use perionav::core::traffic::{EdgeLookup, SpeedUpdate, TrafficKey, TrafficOverlay};
+use perionav::core::Graph;
+use perionav::reader::traffic_reader::read_speed_updates;
+use perionav::router::Router;
+use rstest::rstest;
+
+mod create_graph;
+
+fn update(key: TrafficKey, speed: f64, expires_at: Option<f64>) -> SpeedUpdate {
+    SpeedUpdate { key, speed, expires_at }
+}
+
+#[test]
+fn test_overlay() {
+    let g = create_graph::create_road_classes_graph();
+    let lookup = EdgeLookup::new(&g);
+    let mut overlay = TrafficOverlay::new();
+    assert!(overlay.is_empty());
+
+    //the edge between osm nodes 13 and 12 is the edge between indices 2 and 3
+    overlay.apply(update(TrafficKey::Way(1), 36.0, None), &lookup).unwrap();
+    overlay.apply(update(TrafficKey::Edge(13, 12), 72.0, Some(100.0)), &lookup).unwrap();
+    assert_eq!(overlay.get_updates().len(), 2);
+
+    assert_eq!(overlay.get_speed(Some(1), 0, 1, 0.0), Some(10.0));
+    assert_eq!(overlay.get_speed(Some(2), 0, 1, 0.0), None);
+    assert_eq!(overlay.get_speed(None, 0, 1, 0.0), None);
+
+    //the edge update has priority over the way update until it expires
+    assert_eq!(overlay.get_speed(Some(1), 2, 3, 99.0), Some(20.0));
+    assert_eq!(overlay.get_speed(Some(1), 2, 3, 100.0), Some(10.0));
+
+    overlay.remove_expired(100.0);
+    assert_eq!(overlay.get_updates().len(), 1);
+
+    overlay.clear();
+    assert!(overlay.is_empty());
+}
+
+#[rstest]
+fn test_route_with_traffic(#[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType) {
+    let g = create_graph::create_road_classes_graph();
+
+    //a traffic jam on the first part of the motorway until time 100
+    let mut overlay = TrafficOverlay::new();
+    overlay
+        .apply(update(TrafficKey::Edge(10, 11), 3.6, Some(100.0)), &EdgeLookup::new(&g))
+        .unwrap();
+    let overlay = Rc::new(overlay);
+
+    let weight_calculator = create_traffic_weight_calculator(VehicleTypes::Car, Rc::clone(&overlay), 50.0);
+    let opts = RoutingAlgorithmOptions::with_weight_calculator(true, algorithm_type, weight_calculator);
+    let result = g.route(&opts, 0, 3).unwrap();
+    assert_eq!(result.paths[0].get_nodes(), vec![0, 2, 3]);
+    assert!((result.weight - 2.4).abs() < 1E-9);
+
+    let weight_calculator = create_traffic_weight_calculator(VehicleTypes::Car, overlay, 150.0);
+    let opts = RoutingAlgorithmOptions::with_weight_calculator(true, AlgorithmType::DIJKSTRA, weight_calculator);
+    let result = g.route(&opts, 0, 3).unwrap();
+    assert_eq!(result.paths[0].get_nodes(), vec![0, 1, 3]);
+}
+
+#[test]
+fn test_way_update() {
+    let g = create_graph::create_road_classes_graph();
+
+    //the whole residential road gets faster than the motorway
+    let mut overlay = TrafficOverlay::new();
+    overlay.apply(update(TrafficKey::Way(2), 360.0, None), &EdgeLookup::new(&g)).unwrap();
+
+    let weight_calculator = create_traffic_weight_calculator(VehicleTypes::Car, Rc::new(overlay), 0.0);
+    let opts = RoutingAlgorithmOptions::with_weight_calculator(true, AlgorithmType::DIJKSTRA, weight_calculator);
+    let result = g.route(&opts, 0, 3).unwrap();
+    assert_eq!(result.paths[0].get_nodes(), vec![0, 2, 3]);
+    assert!((result.weight - 0.24).abs() < 1E-9);
+}
+
+#[test]
+fn test_read_speed_updates() {
+    let updates = read_speed_updates("tests/data/speed_updates.csv").unwrap();
+    assert_eq!(updates.len(), 2);
+    assert_eq!(updates[0].key, TrafficKey::Way(12));
+    assert_eq!(updates[0].expires_at, None);
+    assert_eq!(updates[1].key, TrafficKey::Edge(1, 2));
+    assert_eq!(updates[1].speed, 50.0);
+    assert_eq!(updates[1].expires_at, Some(1700000000.0));
+}
+
+#[rstest]
+#[case("tests/data/speed_updates_missing_field.csv")]
+//only the header can be skipped
+#[case("tests/data/speed_updates_invalid_first_line.csv")]
+fn test_read_invalid_speed_updates(#[case] file_name: &str) {
+    let error = read_speed_updates(file_name).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[rstest]
+//13 isn't next to 10 and 99 isn't in the graph
+#[case(TrafficKey::Edge(10, 13))]
+#[case(TrafficKey::Edge(10, 99))]
+fn test_unknown_edge(#[case] key: TrafficKey) {
+    let g = create_graph::create_road_classes_graph();
+    let mut overlay = TrafficOverlay::new();
+    let result = overlay.apply(update(key, 36.0, None), &EdgeLookup::new(&g));
+    assert!(result.is_err_and(|e| e.kind() == ErrorKind::InvalidData));
+}
+
+#[test]
+fn test_router_speed_updates() {
+    let mut router = Router::new(create_graph::create_road_classes_graph());
+    let (from, to) = ((51.0, 3.7), (51.001, 3.7));
+    assert_eq!(router.route_with_traffic(from, to, 0.0).unwrap().paths[0].get_nodes(), vec![0, 1, 3]);
+
+    //a traffic jam on the motorway, by osm ids
+    router.apply_speed_updates(vec![update(TrafficKey::Edge(11, 13), 3.6, None)]).unwrap();
+    assert_eq!(router.route_with_traffic(from, to, 0.0).unwrap().paths[0].get_nodes(), vec![0, 2, 3]);
+
+    //nothing is applied when one of the edges is unknown
+    let updates = vec![update(TrafficKey::Way(2), 3.6, None), update(TrafficKey::Edge(10, 13), 3.6, None)];
+    assert!(router.apply_speed_updates(updates).is_err());
+    assert_eq!(router.get_speed_updates().len(), 1);
+}
+
+#[test]
+fn test_replayed_speed_updates() {
+    let mut router = Router::new(create_graph::create_road_classes_graph());
+    let (from, to) = ((51.0, 3.7), (51.001, 3.7));
+
+    //an update of a replayed feed that expired long ago is still used for queries before it expires
+    router.apply_speed_updates(vec![update(TrafficKey::Way(1), 3.6, Some(100.0))]).unwrap();
+    assert_eq!(router.route_with_traffic(from, to, 50.0).unwrap().paths[0].get_nodes(), vec![0, 2, 3]);
+    assert_eq!(router.route_with_traffic(from, to, 100.0).unwrap().paths[0].get_nodes(), vec![0, 1, 3]);
+
+    router.remove_expired_speed_updates(99.0);
+    assert_eq!(router.get_speed_updates().len(), 1);
+    router.remove_expired_speed_updates(100.0);
+    assert!(router.get_speed_updates().is_empty());
+}
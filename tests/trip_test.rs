@@ -0,0 +1,93 @@
+use perionav::core::routing::{
+    options::WeightType,
+    trip::{create_weight_matrix, optimize_order, optimize_trip, TripOptions},
+};
+use rstest::rstest;
+
+mod create_graph;
+
+//waypoints on a line, the weight is the distance between the positions
+fn create_line_matrix(positions: &[f64]) -> Vec<Vec<f64>> {
+    positions.iter().map(|a| positions.iter().map(|b| (a - b).abs()).collect()).collect()
+}
+
+fn order_weight(matrix: &[Vec<f64>], order: &[usize]) -> f64 {
+    order.windows(2).map(|w| matrix[w[0]][w[1]]).sum()
+}
+
+#[test]
+fn test_optimize_order_open_trip() {
+    let matrix = create_line_matrix(&[0.0, 5.0, 1.0, 4.0, 2.0, 3.0]);
+    let trip_opts = TripOptions {
+        round_trip: false,
+        fixed_end: false,
+    };
+
+    assert_eq!(optimize_order(&matrix, &trip_opts), vec![0, 2, 4, 5, 3, 1]);
+}
+
+#[test]
+fn test_optimize_order_fixed_end() {
+    let matrix = create_line_matrix(&[0.0, 5.0, 1.0, 4.0, 2.0, 3.0]);
+    let trip_opts = TripOptions {
+        round_trip: false,
+        fixed_end: true,
+    };
+
+    let order = optimize_order(&matrix, &trip_opts);
+    assert_eq!(order.first(), Some(&0));
+    assert_eq!(order.last(), Some(&5));
+    assert_eq!(order_weight(&matrix, &order), 7.0);
+}
+
+#[test]
+fn test_optimize_order_improves_nearest_neighbor() {
+    //nearest neighbor goes to 1 first, but then has to come back all the way
+    let matrix = create_line_matrix(&[0.0, 1.0, -1.5, 2.0, -3.0, 4.0]);
+    let trip_opts = TripOptions {
+        round_trip: false,
+        fixed_end: false,
+    };
+
+    let order = optimize_order(&matrix, &trip_opts);
+    assert_eq!(order_weight(&matrix, &order), 10.0);
+}
+
+#[test]
+fn test_weight_matrix() {
+    let g = create_graph::create_complex_graph();
+    let matrix = create_weight_matrix(&g, &WeightType::DISTANCE, &[3, 6, 2]);
+
+    assert_eq!(matrix, vec![vec![0.0, 8.0, 5.0], vec![8.0, 0.0, 4.0], vec![5.0, 4.0, 0.0]]);
+}
+
+#[test]
+fn test_optimize_round_trip() {
+    let g = create_graph::create_complex_graph();
+
+    let result = optimize_trip(&g, &WeightType::DISTANCE, &[3, 6, 2, 5], &TripOptions::default()).unwrap();
+
+    assert_eq!(result.order.len(), 4);
+    assert_eq!(result.order[0], 0);
+    assert_eq!(result.route.legs.len(), 4);
+
+    let nodes = result.route.path.get_nodes();
+    assert_eq!(nodes.first(), Some(&3));
+    assert_eq!(nodes.last(), Some(&3));
+}
+
+#[rstest]
+fn test_optimize_trip_few_waypoints(#[values(true, false)] round_trip: bool) {
+    let g = create_graph::create_complex_graph();
+    let trip_opts = TripOptions {
+        round_trip,
+        ..Default::default()
+    };
+
+    assert!(optimize_trip(&g, &WeightType::DISTANCE, &[], &trip_opts).is_none());
+
+    let result = optimize_trip(&g, &WeightType::DISTANCE, &[3], &trip_opts).unwrap();
+    assert_eq!(result.order, vec![0]);
+    assert!(result.route.legs.is_empty());
+    assert!(result.route.path.get_nodes().is_empty());
+}
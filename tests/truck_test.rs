@@ -0,0 +1,62 @@
+use perionav::core::edge::{PhysicalRestrictions, VehicleTypes};
+use perionav::core::routing::options::{
+    create_dimensions_weight_calculator, create_vehicle_weight_calculator, AlgorithmType, RoutingAlgorithmOptions, WeightType,
+};
+use perionav::core::weight::VehicleDimensions;
+use perionav::core::Graph;
+use rstest::rstest;
+
+mod create_graph;
+
+fn route(algorithm_type: AlgorithmType, dimensions: Option<VehicleDimensions>) -> Option<(f64, Vec<usize>)> {
+    let g = create_graph::create_truck_graph();
+    let weight_calculator = create_vehicle_weight_calculator(&WeightType::DISTANCE, VehicleTypes::Hgv);
+    let weight_calculator = match dimensions {
+        Some(dimensions) => create_dimensions_weight_calculator(weight_calculator, dimensions),
+        None => weight_calculator,
+    };
+    let opts = RoutingAlgorithmOptions::with_weight_calculator(true, algorithm_type, weight_calculator);
+
+    g.route(&opts, 0, 3).map(|result| (result.weight, result.paths[0].get_nodes()))
+}
+
+fn truck(weight: f64, height: f64, hazmat: bool) -> Option<VehicleDimensions> {
+    Some(VehicleDimensions {
+        weight,
+        axle_load: weight / 3.0,
+        height,
+        width: 2.5,
+        length: 12.0,
+        hazmat,
+    })
+}
+
+#[rstest]
+#[case(None, Some((20.0, vec![0, 1, 3])))]
+#[case(truck(12.0, 3.4, false), Some((20.0, vec![0, 1, 3])))]
+//a limit equal to the dimension is allowed
+#[case(truck(12.0, 3.5, false), Some((20.0, vec![0, 1, 3])))]
+#[case(truck(7.0, 4.0, false), Some((24.0, vec![0, 2, 3])))]
+#[case(truck(7.0, 4.0, true), Some((40.0, vec![0, 4, 3])))]
+#[case(truck(12.0, 4.0, false), Some((40.0, vec![0, 4, 3])))]
+//too heavy for 0 -> 2 -> 3, too high for 0 -> 1 -> 3 and too wide for 0 -> 4 -> 3
+#[case(truck(12.0, 4.0, false).map(|truck| VehicleDimensions { width: 2.6, ..truck }), None)]
+fn test_dimensions(
+    #[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType,
+    #[case] dimensions: Option<VehicleDimensions>,
+    #[case] expected: Option<(f64, Vec<usize>)>,
+) {
+    assert_eq!(route(algorithm_type, dimensions), expected);
+}
+
+#[test]
+fn test_fits() {
+    let dimensions = truck(40.0, 4.0, false).unwrap();
+    assert!(dimensions.fits(&Default::default()));
+
+    let restrictions = PhysicalRestrictions {
+        max_width: Some(2.3),
+        ..Default::default()
+    };
+    assert!(!dimensions.fits(&restrictions));
+}
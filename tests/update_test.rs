@@ -0,0 +1,87 @@
+use std::io::ErrorKind;
+
+use perionav::core::edge::VehicleTypes;
+use perionav::core::Graph;
+use perionav::reader::osm_updater::UpdatableGraph;
+use perionav::reader::profile::Profile;
+
+//(osm id, osm id, distance in dm) of every directed edge, graph indices differ between an updated graph and a new one
+fn summarize(g: &impl Graph) -> Vec<(i64, i64, i64)> {
+    let osm_id = |node: usize| g.get_node(node).unwrap().osm_id;
+
+    let mut edges = vec![];
+    for node in 0..g.get_nr_nodes() {
+        g.do_for_all_neighbors(node, false, |adj_node| {
+            let info = g
+                .get_directed_vehicle_specific_edge_information(node, adj_node, false, VehicleTypes::Car)
+                .unwrap();
+            edges.push((osm_id(node), osm_id(adj_node), (info.get_distance() * 10.0).round() as i64));
+        });
+    }
+    edges.sort();
+
+    edges
+}
+
+fn read(file_name: &str) -> UpdatableGraph<'_> {
+    UpdatableGraph::new(file_name, vec![Profile::car()]).unwrap()
+}
+
+#[test]
+fn test_apply_changes() {
+    let mut updatable = read("tests/data/update_network.osm");
+    updatable.apply_changes("tests/data/update_network.osc").unwrap();
+
+    let expected = read("tests/data/update_network_updated.osm");
+    assert_eq!(summarize(updatable.graph()), summarize(expected.graph()));
+}
+
+#[test]
+fn test_components() {
+    let mut updatable = read("tests/data/update_network.osm");
+
+    //the bollard at 10 cuts off 11 for cars
+    let node_11 = updatable.node_index(11).unwrap();
+    assert!(updatable.node_index(10).is_some());
+    assert!(!updatable.is_in_main_component(node_11));
+    let distance_3_4 = summarize(updatable.graph())
+        .into_iter()
+        .find(|(from, to, _)| (*from, *to) == (3, 4))
+        .unwrap()
+        .2;
+
+    updatable.apply_changes("tests/data/update_network.osc").unwrap();
+
+    //without the bollard 10 is only there for shape
+    assert!(updatable.node_index(10).is_none());
+    assert!(updatable.is_in_main_component(node_11));
+
+    //7 is connected in both directions, the oneway to 8 is a dead end
+    assert!(updatable.is_in_main_component(updatable.node_index(7).unwrap()));
+    assert!(!updatable.is_in_main_component(updatable.node_index(8).unwrap()));
+    assert!(updatable.node_index(9).is_none());
+
+    //4 moved further away from 3
+    let edges = summarize(updatable.graph());
+    assert!(edges
+        .iter()
+        .any(|(from, to, distance)| (*from, *to) == (3, 4) && *distance > distance_3_4));
+    assert!(!edges.iter().any(|(from, to, _)| *from == 8 || *to == 8));
+}
+
+#[test]
+fn test_invalid_change_file() {
+    let mut updatable = read("tests/data/update_network.osm");
+    assert!(updatable.apply_changes("tests/data/missing.osc").is_err());
+}
+
+#[test]
+fn test_missing_node() {
+    let mut updatable = read("tests/data/update_network.osm");
+    let before = summarize(updatable.graph());
+
+    //node 12 isn't on a highway and isn't in the change, so its location is unknown
+    let result = updatable.apply_changes("tests/data/update_network_missing_node.osc");
+    assert!(result.is_err_and(|e| e.kind() == ErrorKind::InvalidData && e.to_string().contains("node 12")));
+    assert_eq!(summarize(updatable.graph()), before);
+}
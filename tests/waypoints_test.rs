@@ -0,0 +1,78 @@
+use perionav::core::routing::{
+    options::{AlgorithmType, RoutingAlgorithmOptions, WeightType},
+    waypoints::{route_via, WaypointOptions},
+};
+use perionav::router::Router;
+use rstest::rstest;
+
+mod create_graph;
+
+#[rstest]
+fn test_route_via(#[values(AlgorithmType::DIJKSTRA, AlgorithmType::DIJKSTRA2, AlgorithmType::BIDIRDIJKSTRA)] algorithm_type: AlgorithmType) {
+    let g = create_graph::create_complex_graph();
+    let opts = RoutingAlgorithmOptions::new(true, algorithm_type, WeightType::DISTANCE);
+
+    let result = route_via(&g, &opts, &[3, 6, 2], &WaypointOptions::default()).unwrap();
+
+    let weights: Vec<f64> = result.legs.iter().map(|l| l.weight).collect();
+    assert_eq!(weights, vec![8.0, 4.0]);
+    assert_eq!(result.legs[0].distance, 8.0);
+    assert_eq!(result.path.get_nodes(), vec![3, 1, 4, 0, 6, 2]);
+}
+
+#[test]
+fn test_route_via_pass_through() {
+    let g = create_graph::create_k3_graph();
+    let opts = RoutingAlgorithmOptions::new(true, AlgorithmType::DIJKSTRA, WeightType::DISTANCE);
+
+    let result = route_via(&g, &opts, &[0, 1, 0], &WaypointOptions::default()).unwrap();
+    assert_eq!(result.path.get_nodes(), vec![0, 1, 0]);
+
+    let waypoint_opts = WaypointOptions {
+        pass_through: true,
+        ..Default::default()
+    };
+    let result = route_via(&g, &opts, &[0, 1, 0], &waypoint_opts).unwrap();
+    assert_eq!(result.path.get_nodes(), vec![0, 1, 2, 0]);
+    assert_eq!(result.legs[1].weight, 2.0);
+}
+
+#[test]
+fn test_route_via_headings() {
+    let g = create_graph::create_coordinates_square_graph();
+    let opts = RoutingAlgorithmOptions::new(true, AlgorithmType::DIJKSTRA, WeightType::DISTANCE);
+
+    let north = WaypointOptions {
+        headings: vec![Some(0.0)],
+        ..Default::default()
+    };
+    let result = route_via(&g, &opts, &[0, 3], &north).unwrap();
+    assert_eq!(result.path.get_nodes(), vec![0, 2, 3]);
+
+    let east = WaypointOptions {
+        headings: vec![Some(90.0)],
+        ..Default::default()
+    };
+    let result = route_via(&g, &opts, &[0, 3], &east).unwrap();
+    assert_eq!(result.path.get_nodes(), vec![0, 1, 3]);
+
+    //arrive at 3 while driving north
+    let arrive_north = WaypointOptions {
+        headings: vec![None, Some(0.0)],
+        ..Default::default()
+    };
+    let result = route_via(&g, &opts, &[0, 3], &arrive_north).unwrap();
+    assert_eq!(result.path.get_nodes(), vec![0, 1, 3]);
+}
+
+#[test]
+fn test_router_route_via() {
+    let router = Router::new(create_graph::create_coordinates_square_graph());
+
+    let result = router
+        .route_via(&[(51.0, 3.7), (51.001, 3.701), (51.0, 3.701)], &WaypointOptions::default())
+        .unwrap();
+    assert_eq!(result.legs.len(), 2);
+    assert_eq!(result.path.get_nodes().first(), Some(&0));
+    assert_eq!(result.path.get_nodes().last(), Some(&1));
+}
@@ -0,0 +1,78 @@
+use std::collections::HashSet;
+
+use perionav::core::avoidareas::Area;
+use perionav::core::edge::VehicleTypes;
+use perionav::core::Graph;
+use perionav::reader::edge_list_reader::read_csv_graph;
+use perionav::writer::graph_writer::{write_graph, ExportFormat, ExportOptions};
+use rstest::rstest;
+use serde_json::Value;
+
+fn export(options: &ExportOptions, format: ExportFormat) -> String {
+    let g = read_csv_graph("tests/data/square.csv").unwrap();
+    let mut output = vec![];
+    write_graph(&g, options, format, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn test_csv() {
+    //the nodes are numbered in the order of square.csv: 1 south west, 2 south east, 3 north east, 4 north west
+    let options = ExportOptions {
+        components: vec![HashSet::from([0, 1, 2]), HashSet::from([3])],
+        ..Default::default()
+    };
+    let output = export(&options, ExportFormat::Csv);
+    let lines: Vec<&str> = output.lines().collect();
+
+    assert_eq!(lines.len(), 6);
+    assert!(lines[0].starts_with("from,to,from_osm_id,to_osm_id,distance,forward,backward"));
+    assert_eq!(lines[1], "0,1,1,2,699.77,true,true,50.00,50.00,0,,other,51.0000000,3.7000000,51.0000000,3.7100000");
+    assert!(lines.contains(&"2,3,3,4,708.40,true,false,30.00,,,,other,51.0100000,3.7100000,51.0100000,3.7000000"));
+}
+
+#[test]
+fn test_geojson() {
+    let output = export(&Default::default(), ExportFormat::GeoJson);
+    let json: Value = serde_json::from_str(&output).unwrap();
+
+    let features = json["features"].as_array().unwrap();
+    assert_eq!(features.len(), 5);
+
+    let oneway = features.iter().find(|f| f["properties"]["backward"] == Value::Bool(false)).unwrap();
+    assert_eq!(oneway["geometry"]["coordinates"][0], serde_json::json!([3.71, 51.01]));
+    assert_eq!(oneway["properties"]["forward_speed"].as_f64().unwrap().round(), 30.0);
+    assert!(oneway["properties"]["backward_speed"].is_null());
+}
+
+#[rstest]
+#[case(Some(Area::BBox { min_lat: 50.99, min_lon: 3.69, max_lat: 51.005, max_lon: 3.72 }), None, 4)]
+#[case(None, Some(HashSet::from([0, 1, 2])), 3)]
+#[case(Some(Area::BBox { min_lat: 50.99, min_lon: 3.69, max_lat: 51.005, max_lon: 3.72 }), Some(HashSet::from([2, 3])), 0)]
+fn test_filters(#[case] area: Option<Area>, #[case] nodes: Option<HashSet<usize>>, #[case] expected: usize) {
+    let options = ExportOptions {
+        area,
+        nodes,
+        ..Default::default()
+    };
+    let output = export(&options, ExportFormat::WktCsv);
+    let lines: Vec<&str> = output.lines().skip(1).collect();
+
+    assert_eq!(lines.len(), expected);
+    assert!(lines.iter().all(|l| l.contains(",\"LINESTRING (")));
+}
+
+#[test]
+fn test_inaccessible_edges() {
+    //edges that the vehicle type can't use aren't exported
+    let g = read_csv_graph("tests/data/square.csv").unwrap();
+    let options = ExportOptions {
+        vehicle_type: VehicleTypes::Bike,
+        ..Default::default()
+    };
+    let mut output = vec![];
+    write_graph(&g, &options, ExportFormat::Csv, &mut output).unwrap();
+
+    assert_eq!(String::from_utf8(output).unwrap().lines().count(), 1);
+    assert_eq!(g.get_nr_nodes(), 4);
+}